@@ -0,0 +1,257 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use asefile::AnimationDirection;
+use asefile::AsepriteFile;
+use image::RgbaImage;
+use serde::Serialize;
+
+const ASSETS_DIR: &str = "assets";
+const SOURCE_DIR: &str = "src";
+const MANIFEST_PATH: &str = "assets/animation.manifest.ron";
+
+// Usage: `cargo run --bin assets --features asset_pipeline [-- --check]`
+// Exports every .aseprite file under assets/ to a horizontal PNG strip, an Aseprite-style
+// `.aseprite.json` atlas for `AsepriteSheet`, and one RON manifest of all sheets. Then checks
+// that every `#[asset(path = ...)]` in the source resolves to a file. `--check` skips the export.
+fn main() -> ExitCode {
+    let check_only = std::env::args().any(|arg| arg == "--check");
+
+    let mut errors = vec![];
+    if !check_only {
+        let mut manifest = AnimationManifest::default();
+        for path in find_files(Path::new(ASSETS_DIR), "aseprite") {
+            match export_sheet(&path) {
+                Ok(sheet) => {
+                    println!("Exported {}", path.display());
+                    manifest.sheets.push(sheet);
+                },
+                Err(e) => errors.push(format!("{}: {e}", path.display())),
+            }
+        }
+
+        let pretty = ron::ser::PrettyConfig::default();
+        match ron::ser::to_string_pretty(&manifest, pretty) {
+            Ok(ron) => {
+                if let Err(e) = fs::write(MANIFEST_PATH, ron) {
+                    errors.push(format!("{MANIFEST_PATH}: {e}"));
+                }
+            },
+            Err(e) => errors.push(format!("{MANIFEST_PATH}: {e}")),
+        }
+    }
+
+    for path in find_files(Path::new(SOURCE_DIR), "rs") {
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+        for (line, asset_path) in asset_paths(&source) {
+            if !Path::new(ASSETS_DIR).join(&asset_path).is_file() {
+                errors.push(format!(
+                    "{}:{line}: missing asset \"{asset_path}\"",
+                    path.display(),
+                ));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        println!("All assets OK");
+        return ExitCode::SUCCESS;
+    }
+    eprintln!("{} asset error(s):", errors.len());
+    for error in &errors {
+        eprintln!("  {error}");
+    }
+    ExitCode::FAILURE
+}
+
+/// Recursively list files with the given extension, sorted for stable output
+fn find_files(dir: &Path, extension: &str) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let Ok(entries) = fs::read_dir(dir) else {
+        return files;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(find_files(&path, extension));
+        } else if path.extension().is_some_and(|x| x == extension) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Find the line number and path of every `#[asset(path = "...")]` attribute
+fn asset_paths(source: &str) -> Vec<(usize, String)> {
+    const PREFIX: &str = "#[asset(path = \"";
+
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let rest = &line[line.find(PREFIX)? + PREFIX.len()..];
+            let path = &rest[..rest.find('"')?];
+            Some((i + 1, path.to_string()))
+        })
+        .collect()
+}
+
+#[derive(Serialize, Default)]
+struct AnimationManifest {
+    sheets: Vec<SheetManifest>,
+}
+
+#[derive(Serialize)]
+struct SheetManifest {
+    /// Relative to the assets directory
+    source: String,
+    image: String,
+    atlas: String,
+    frame_size: (u32, u32),
+    /// In milliseconds
+    frame_durations: Vec<u32>,
+    tags: Vec<TagManifest>,
+    /// Visible layers, each exported to its own strip if there's more than one
+    layers: Vec<String>,
+}
+
+#[derive(Serialize, Clone)]
+struct TagManifest {
+    name: String,
+    from: u32,
+    to: u32,
+    direction: String,
+}
+
+/// The JSON array format that Aseprite exports and `AsepriteSheet` loads
+#[derive(Serialize)]
+struct AtlasJson {
+    frames: Vec<AtlasFrame>,
+    meta: AtlasMeta,
+}
+
+#[derive(Serialize)]
+struct AtlasFrame {
+    frame: AtlasRect,
+    duration: u32,
+}
+
+#[derive(Serialize)]
+struct AtlasRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Serialize)]
+struct AtlasMeta {
+    image: String,
+    size: AtlasSize,
+    #[serde(rename = "frameTags")]
+    frame_tags: Vec<TagManifest>,
+}
+
+#[derive(Serialize)]
+struct AtlasSize {
+    w: u32,
+    h: u32,
+}
+
+fn export_sheet(path: &Path) -> Result<SheetManifest, Box<dyn Error>> {
+    let ase = AsepriteFile::read_file(path)?;
+    let (w, h) = (ase.width() as u32, ase.height() as u32);
+    let frame_count = ase.num_frames();
+
+    let stem = path
+        .file_stem()
+        .and_then(|x| x.to_str())
+        .ok_or("invalid file name")?;
+    let dir = path.parent().ok_or("invalid path")?;
+    let image_name = format!("{stem}.atlas.png");
+    let atlas_name = format!("{stem}.aseprite.json");
+
+    // Frames are laid out left to right
+    let mut strip = RgbaImage::new(w * frame_count, h);
+    let mut frames = vec![];
+    for i in 0..frame_count {
+        let frame = ase.frame(i);
+        image::imageops::replace(&mut strip, &frame.image(), (i * w) as i64, 0);
+        frames.push(AtlasFrame {
+            frame: AtlasRect {
+                x: i * w,
+                y: 0,
+                w,
+                h,
+            },
+            duration: frame.duration(),
+        });
+    }
+    strip.save(dir.join(&image_name))?;
+
+    let tags = (0..ase.num_tags())
+        .map(|i| {
+            let tag = ase.tag(i);
+            TagManifest {
+                name: tag.name().to_string(),
+                from: tag.from_frame(),
+                to: tag.to_frame(),
+                direction: match tag.animation_direction() {
+                    AnimationDirection::Reverse => "reverse",
+                    AnimationDirection::PingPong => "pingpong",
+                    _ => "forward",
+                }
+                .to_string(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let layers = ase
+        .layers()
+        .filter(|layer| layer.is_visible())
+        .collect::<Vec<_>>();
+    if layers.len() > 1 {
+        for layer in &layers {
+            let mut strip = RgbaImage::new(w * frame_count, h);
+            for i in 0..frame_count {
+                image::imageops::replace(&mut strip, &layer.frame(i).image(), (i * w) as i64, 0);
+            }
+            let name = layer.name().to_lowercase().replace(' ', "_");
+            strip.save(dir.join(format!("{stem}.{name}.atlas.png")))?;
+        }
+    }
+
+    let atlas = AtlasJson {
+        frames,
+        meta: AtlasMeta {
+            image: image_name.clone(),
+            size: AtlasSize {
+                w: w * frame_count,
+                h,
+            },
+            frame_tags: tags.clone(),
+        },
+    };
+    fs::write(dir.join(&atlas_name), serde_json::to_string_pretty(&atlas)?)?;
+
+    let relative_dir = dir.strip_prefix(ASSETS_DIR).unwrap_or(dir);
+    let relative = |name: &str| relative_dir.join(name).to_string_lossy().into_owned();
+    Ok(SheetManifest {
+        source: relative(&format!("{stem}.aseprite")),
+        image: relative(&image_name),
+        atlas: relative(&atlas_name),
+        frame_size: (w, h),
+        frame_durations: (0..frame_count).map(|i| ase.frame(i).duration()).collect(),
+        tags,
+        layers: layers
+            .iter()
+            .map(|layer| layer.name().to_string())
+            .collect(),
+    })
+}
@@ -1,9 +1,38 @@
 // Disable console on windows for release builds
 #![cfg_attr(not(feature = "dev"), windows_subsystem = "windows")]
 
+use std::process::ExitCode;
+
 use bevy::prelude::*;
 use sai_defects::AppPlugin;
+use sai_defects::AssetCheckFailed;
+use sai_defects::AssetCheckPlugin;
+use sai_defects::FloorSeed;
+
+// Usage: `cargo run -- --check-assets` to validate every asset and the config, then exit
+// Usage: `cargo run -- --floor [seed]` to play a procedural floor, from a random seed if none given
+fn main() -> ExitCode {
+    let args = std::env::args().collect::<Vec<_>>();
+
+    let mut app = App::new();
+    if args.iter().any(|arg| arg == "--check-assets") {
+        app.add_plugins(AssetCheckPlugin).run();
+        return if app.world.resource::<AssetCheckFailed>().0 {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        };
+    }
+
+    app.add_plugins(AppPlugin);
+    if let Some(i) = args.iter().position(|arg| arg == "--floor") {
+        let seed = args
+            .get(i + 1)
+            .and_then(|x| x.parse().ok())
+            .unwrap_or_else(rand::random);
+        app.insert_resource(FloorSeed(seed));
+    }
+    app.run();
 
-fn main() {
-    App::new().add_plugins(AppPlugin).run();
+    ExitCode::SUCCESS
 }
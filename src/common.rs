@@ -5,7 +5,7 @@ pub mod camera;
 pub mod config;
 #[cfg(feature = "dev")]
 mod debug;
-mod music;
+pub mod music;
 mod physics;
 pub mod theme;
 pub mod window;
@@ -6,18 +6,27 @@ use serde::Serialize;
 
 use crate::common::theme::ThemeConfig;
 use crate::common::window::WindowConfig;
+use crate::game::combat::CombatConfig;
 
 pub struct ConfigPlugin;
 
 impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(ConfigLoaderPlugin).add_systems(
+            PreUpdate,
+            apply_config.run_if(on_event::<AssetEvent<Config>>()),
+        );
+    }
+}
+
+/// Loads the config without applying it, for apps without a window
+pub struct ConfigLoaderPlugin;
+
+impl Plugin for ConfigLoaderPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Config>()
             .add_plugins(RonAssetPlugin::<Config>::new(&["config.ron"]))
-            .add_systems(Startup, load_config)
-            .add_systems(
-                PreUpdate,
-                apply_config.run_if(on_event::<AssetEvent<Config>>()),
-            );
+            .add_systems(Startup, load_config);
     }
 }
 
@@ -30,6 +39,8 @@ pub struct ConfigHandle(pub Handle<Config>);
 pub struct Config {
     pub window: WindowConfig,
     pub theme: ThemeConfig,
+    #[serde(default)]
+    pub combat: CombatConfig,
     // TODO: Volume
     // TODO: Mute when out of focus
     // TODO: Keybindings
@@ -49,9 +60,13 @@ fn apply_config(world: &mut World, mut reader: Local<ManualEventReader<AssetEven
 
     info!("Applying config");
     world.resource_scope(|world, config: Mut<Assets<Config>>| {
-        let config = config.get(&world.resource::<ConfigHandle>().0).unwrap();
+        let Some(config) = config.get(&world.resource::<ConfigHandle>().0) else {
+            error!("Config was unloaded before it could be applied");
+            return;
+        };
 
         config.window.apply(world);
         config.theme.apply(world);
+        config.combat.apply(world);
     });
 }
@@ -1,7 +1,10 @@
+pub mod ally;
+pub mod bark;
 pub mod body;
 pub mod enemy;
 pub mod health;
 pub mod intent;
+pub mod inventory;
 pub mod player;
 
 use bevy::prelude::*;
@@ -11,9 +14,11 @@ use bevy_rapier2d::prelude::*;
 
 use crate::game::actor::health::Health;
 use crate::game::actor::intent::ActorIntent;
+use crate::game::combat::status::StatusEffects;
 use crate::game::combat::Faction;
 use crate::game::combat::COLLISION_GROUP;
 use crate::util::animation::facing::Facing;
+use crate::util::animation::frame::AsepriteSheet;
 use crate::util::y_sort::YSort;
 
 pub struct ActorPlugin;
@@ -26,10 +31,13 @@ impl Plugin for ActorPlugin {
             .init_collection::<ActorAssets>();
 
         app.add_plugins((
+            ally::AllyPlugin,
+            bark::BarkPlugin,
             body::BodyPlugin,
             enemy::EnemyPlugin,
             health::HealthPlugin,
             intent::IntentPlugin,
+            inventory::InventoryPlugin,
             player::PlayerPlugin,
         ));
     }
@@ -44,6 +52,9 @@ pub struct ActorAssets {
     pub gnoll_green: Handle<Image>,
     #[asset(path = "image/actor/gnoll_blue.png")]
     pub gnoll_blue: Handle<Image>,
+    /// Frame layout shared by every gnoll color
+    #[asset(path = "image/actor/gnoll.aseprite.json")]
+    pub gnoll_sheet: Handle<AsepriteSheet>,
 
     #[asset(path = "sound/sfx/walk.wav")]
     step: Handle<AudioSource>,
@@ -96,6 +107,7 @@ pub struct ActorBundle {
     pub actor_intent: ActorIntent,
     pub facing: Facing,
     pub health: Health,
+    pub status_effects: StatusEffects,
     pub velocity: Velocity,
     pub y_sort: YSort,
     pub rigid_body: RigidBody,
@@ -113,6 +125,7 @@ impl Default for ActorBundle {
             actor_intent: default(),
             facing: default(),
             health: Health::full(100.0),
+            status_effects: default(),
             y_sort: YSort,
             velocity: default(),
             rigid_body: default(),
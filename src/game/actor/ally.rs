@@ -0,0 +1,390 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::thread_rng;
+
+use crate::common::UpdateSet;
+use crate::game::actor::bark::Bark;
+use crate::game::actor::bark::BarkEvent;
+use crate::game::actor::body::BodyTemplate;
+use crate::game::actor::enemy::has_line_of_sight;
+use crate::game::actor::enemy::random_name;
+use crate::game::actor::health::Health;
+use crate::game::actor::intent::ActorIntent;
+use crate::game::actor::player::PlayerControl;
+use crate::game::actor::player::Playthrough;
+use crate::game::actor::Actor;
+use crate::game::actor::ActorAssets;
+use crate::game::actor::ActorBundle;
+use crate::game::combat::weapon::Weapon;
+use crate::game::combat::CombatAssets;
+use crate::game::combat::Faction;
+use crate::game::combat::HurtEffects;
+use crate::game::level::field::FieldError;
+use crate::game::level::registry::register_entity_spawner;
+use crate::game::level::registry::FromLevelEntity;
+use crate::game::level::registry::LevelEntity;
+use crate::util::ui::health_bar::HealthBarTemplate;
+use crate::util::ui::nametag::NametagTemplate;
+use crate::util::ui::status_icons::StatusIconsTemplate;
+use crate::util::vfx::DropShadowTemplate;
+use crate::util::vfx::VfxAssets;
+
+pub struct AllyPlugin;
+
+impl Plugin for AllyPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Captive>()
+            .add_systems(Update, free_captives.in_set(UpdateSet::Update));
+
+        app.register_type::<AllyAi>()
+            .register_type::<AllyName>()
+            .add_systems(
+                Update,
+                (
+                    respawn_followers.in_set(UpdateSet::Start),
+                    record_ally_intents.in_set(UpdateSet::RecordIntents),
+                ),
+            );
+        register_entity_spawner(app, "captive", |commands, world, spawn| {
+            Ok(AllyTemplate::from_level_entity(spawn)?.spawn(
+                commands,
+                world.resource::<ActorAssets>(),
+                world.resource::<CombatAssets>(),
+                world.resource::<VfxAssets>(),
+            ))
+        });
+    }
+}
+
+const FACTION: Faction = Faction::Defector;
+const FREE_RADIUS: f32 = 16.0;
+/// How close to Sai a following ally must be to leave a level with them
+const ESCAPE_RADIUS: f32 = 64.0;
+
+/// A tied-up gnoll waiting for Sai to free them
+#[derive(Component, Reflect)]
+pub struct Captive;
+
+fn free_captives(
+    mut commands: Commands,
+    mut bark_events: EventWriter<BarkEvent>,
+    mut captive_query: Query<(Entity, &GlobalTransform, &mut CollisionGroups), With<Captive>>,
+    player_query: Query<&GlobalTransform, (With<PlayerControl>, With<ActorIntent>)>,
+) {
+    let Ok(player_gt) = player_query.get_single() else {
+        return;
+    };
+    let player_pos = player_gt.translation().xy();
+
+    for (entity, gt, mut collision_groups) in &mut captive_query {
+        if gt.translation().xy().distance(player_pos) > FREE_RADIUS {
+            continue;
+        }
+
+        // Become a valid target now that they've joined Sai's side
+        let hurtbox_groups = FACTION.hurtbox_groups();
+        collision_groups.memberships |= hurtbox_groups.memberships;
+        collision_groups.filters |= hurtbox_groups.filters;
+
+        commands.entity(entity).remove::<Captive>();
+        bark_events.send(BarkEvent {
+            actor: entity,
+            bark: Bark::Freed,
+        });
+    }
+}
+
+#[derive(Reflect, Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum AllyOrder {
+    #[default]
+    Follow,
+    Stay,
+}
+
+#[derive(Component, Reflect)]
+pub struct AllyAi {
+    pub order: AllyOrder,
+    pub sight_radius: f32,
+    pub attack_radius: f32,
+    pub follow_distance: f32,
+    /// Allies closer together than this push apart
+    pub spacing: f32,
+    target: Option<Entity>,
+}
+
+impl Default for AllyAi {
+    fn default() -> Self {
+        Self {
+            order: default(),
+            sight_radius: 90.0,
+            attack_radius: 20.0,
+            follow_distance: 28.0,
+            spacing: 18.0,
+            target: None,
+        }
+    }
+}
+
+fn record_ally_intents(
+    mut ally_query: Query<
+        (Entity, &mut AllyAi, &mut ActorIntent, &GlobalTransform),
+        Without<Captive>,
+    >,
+    captive_query: Query<(), With<Captive>>,
+    player_query: Query<&GlobalTransform, (With<PlayerControl>, With<ActorIntent>)>,
+    actor_query: Query<(Entity, &Actor, &GlobalTransform), With<ActorIntent>>,
+    rapier_context: Res<RapierContext>,
+) {
+    let player_pos = player_query
+        .get_single()
+        .ok()
+        .map(|gt| gt.translation().xy());
+    let ally_positions = ally_query
+        .iter()
+        .map(|(entity, _, _, gt)| (entity, gt.translation().xy()))
+        .collect::<Vec<_>>();
+
+    for (entity, mut ally, mut intent, ally_gt) in &mut ally_query {
+        let pos = ally_gt.translation().xy();
+        let sight_radius = ally.sight_radius;
+        intent.attack = None;
+        intent.movement = Vec2::ZERO;
+
+        // Forget targets that died or got away
+        ally.target = ally.target.filter(|&target| {
+            actor_query
+                .get(target)
+                .is_ok_and(|(_, _, gt)| gt.translation().xy().distance(pos) <= sight_radius)
+        });
+
+        // Pick the nearest visible hostile
+        if ally.target.is_none() {
+            ally.target = actor_query
+                .iter()
+                .filter(|&(target, actor, _)| {
+                    FACTION.is_hostile(actor.faction) && !captive_query.contains(target)
+                })
+                .map(|(target, _, gt)| (target, gt.translation().xy()))
+                .filter(|&(_, target_pos)| {
+                    target_pos.distance(pos) <= sight_radius
+                        && has_line_of_sight(&rapier_context, pos, target_pos)
+                })
+                .min_by(|(_, a), (_, b)| a.distance(pos).total_cmp(&b.distance(pos)))
+                .map(|(target, _)| target);
+        }
+
+        if let Some((_, _, target_gt)) = ally.target.and_then(|target| actor_query.get(target).ok())
+        {
+            // Fight the target
+            let target_delta = target_gt.translation().xy() - pos;
+            let target_direction = target_delta.normalize_or_zero();
+            if target_delta.length() <= ally.attack_radius {
+                intent.attack = Some(target_direction);
+            } else {
+                intent.movement = target_direction;
+            }
+        } else if let (AllyOrder::Follow, Some(player_pos)) = (ally.order, player_pos) {
+            // Keep up with Sai
+            let player_delta = player_pos - pos;
+            if player_delta.length() > ally.follow_distance {
+                intent.movement = player_delta.normalize();
+            }
+        }
+
+        // Avoid crowding other allies
+        let separation = ally_positions
+            .iter()
+            .filter(|&&(other, _)| other != entity)
+            .map(|&(_, other_pos)| pos - other_pos)
+            .filter(|delta| delta.length() < ally.spacing)
+            .map(|delta| delta.normalize_or_zero() * (1.0 - delta.length() / ally.spacing))
+            .sum::<Vec2>();
+        intent.movement = (intent.movement + separation).clamp_length_max(1.0);
+    }
+}
+
+/// The name an ally was spawned with, so they keep it between levels
+#[derive(Component, Reflect)]
+pub struct AllyName(pub String);
+
+/// An ally who left a level with Sai
+#[derive(Reflect, Clone, Debug)]
+pub struct Follower {
+    pub name: String,
+    pub health: f32,
+}
+
+pub type FollowerQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static AllyAi,
+        &'static AllyName,
+        &'static Health,
+        &'static GlobalTransform,
+    ),
+    (With<ActorIntent>, Without<Captive>),
+>;
+
+/// The allies following Sai closely enough to leave the level with them
+pub fn gather_followers(player_pos: Vec2, ally_query: &FollowerQuery) -> Vec<Follower> {
+    ally_query
+        .iter()
+        .filter(|(ally, _, _, gt)| {
+            ally.order == AllyOrder::Follow
+                && gt.translation().xy().distance(player_pos) <= ESCAPE_RADIUS
+        })
+        .map(|(_, name, health, _)| Follower {
+            name: name.0.clone(),
+            health: health.current,
+        })
+        .collect()
+}
+
+/// Spawn the allies who left the previous level with Sai around Sai's entry
+fn respawn_followers(
+    mut commands: Commands,
+    mut playthrough: ResMut<Playthrough>,
+    player_query: Query<(&Parent, &Transform), Added<PlayerControl>>,
+    actor_assets: Res<ActorAssets>,
+    combat_assets: Res<CombatAssets>,
+    vfx_assets: Res<VfxAssets>,
+) {
+    let Ok((parent, player_transform)) = player_query.get_single() else {
+        return;
+    };
+
+    let count = playthrough.followers.len();
+    for (i, follower) in playthrough.followers.drain(..).enumerate() {
+        // Spread out around Sai instead of spawning inside each other
+        let offset = Vec2::from_angle(TAU * i as f32 / count as f32) * 12.0;
+        let ally = AllyTemplate {
+            transform: player_transform
+                .with_translation(player_transform.translation + offset.extend(0.0)),
+            name: follower.name,
+            health: follower.health,
+            is_captive: false,
+        }
+        .spawn(&mut commands, &actor_assets, &combat_assets, &vfx_assets);
+        commands.entity(ally).set_parent(parent.get());
+    }
+}
+
+pub struct AllyTemplate {
+    pub transform: Transform,
+    pub name: String,
+    pub health: f32,
+    pub is_captive: bool,
+}
+
+impl Default for AllyTemplate {
+    fn default() -> Self {
+        Self {
+            transform: default(),
+            name: "Unnamed".to_string(),
+            health: 30.0,
+            is_captive: true,
+        }
+    }
+}
+
+impl FromLevelEntity for AllyTemplate {
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError> {
+        let base = Self::default();
+        Ok(Self {
+            transform: entity.transform,
+            name: entity
+                .field("name")?
+                .unwrap_or_else(|| random_name(thread_rng())),
+            health: entity.field_or("health", base.health)?,
+            ..base
+        })
+    }
+}
+
+impl AllyTemplate {
+    pub fn with_random_name(mut self) -> Self {
+        self.name = random_name(thread_rng());
+        self
+    }
+
+    pub fn spawn(
+        self,
+        commands: &mut Commands,
+        actor_assets: &ActorAssets,
+        combat_assets: &CombatAssets,
+        vfx_assets: &VfxAssets,
+    ) -> Entity {
+        // Children
+        let body = BodyTemplate {
+            texture: actor_assets.gnoll_blue.clone(),
+            offset: Transform::from_xyz(2.0, 11.0, 0.0),
+            step_sound: None,
+            is_corpse: false,
+            sheet: Some(actor_assets.gnoll_sheet.clone()),
+        }
+        .spawn(commands);
+        let drop_shadow = DropShadowTemplate::default().spawn(commands, vfx_assets);
+        let nametag = NametagTemplate {
+            offset: Transform::from_xyz(0.0, 26.0, 0.0),
+            name: self.name.clone(),
+        }
+        .spawn(commands);
+        let health_bar = HealthBarTemplate {
+            offset: Transform::from_xyz(0.0, -6.0, 0.0),
+        }
+        .spawn(commands);
+        let status_icons = StatusIconsTemplate {
+            offset: Transform::from_xyz(13.5, -6.0, 0.0),
+        }
+        .spawn(commands);
+
+        // Parent
+        let mut actor_bundle = ActorBundle {
+            health: Health::full(self.health),
+            actor: Actor {
+                faction: FACTION,
+                ..Actor::player()
+            },
+            ..default()
+        };
+        if !self.is_captive {
+            actor_bundle = actor_bundle.with_faction(FACTION);
+        }
+        let ally = commands
+            .spawn((
+                Name::new("Ally"),
+                SpatialBundle {
+                    transform: self.transform,
+                    ..default()
+                },
+                actor_bundle,
+                ColliderMassProperties::Mass(1.0),
+                AllyAi::default(),
+                AllyName(self.name),
+                Weapon {
+                    cooldown: 0.6,
+                    ..Weapon::claws(combat_assets)
+                },
+                HurtEffects {
+                    sound: Some(actor_assets.hurt.clone()),
+                    ..default()
+                },
+            ))
+            .add_child(body)
+            .add_child(drop_shadow)
+            .add_child(nametag)
+            .add_child(health_bar)
+            .add_child(status_icons)
+            .id();
+
+        if self.is_captive {
+            // Captives are left out of the fight until they're freed
+            commands.entity(ally).insert(Captive);
+        }
+
+        ally
+    }
+}
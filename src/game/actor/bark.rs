@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use rand::Rng;
+
+use crate::common::UpdateSet;
+use crate::util::ui::speech_bubble::SpeechBubble;
+use crate::util::ui::speech_bubble::SpeechBubbleTemplate;
+use crate::util::DespawnSet;
+
+pub struct BarkPlugin;
+
+impl Plugin for BarkPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BarkEvent>()
+            .add_systems(Update, spawn_barks.in_set(UpdateSet::HandleEvents));
+    }
+}
+
+const ALERTED_LINES: [&str; 6] = [
+    "Who goes there?",
+    "Huh?",
+    "Hey!",
+    "Intruder!",
+    "Show yourself!",
+    "I heard that!",
+];
+const DEFECTION_LINES: [&str; 6] = [
+    "Sai?! Traitor!",
+    "It's the defector!",
+    "Get the traitor!",
+    "Sai! You dog!",
+    "Turncoat!",
+    "Thought you were one of us!",
+];
+const FOUND_CORPSE_LINES: [&str; 5] = [
+    "A body?!",
+    "Who did this?!",
+    "Murder! Raise the alarm!",
+    "They're dead...",
+    "There's a killer about!",
+];
+const FREED_LINES: [&str; 5] = [
+    "Thanks, Sai!",
+    "I'm with you!",
+    "Let's get out of here!",
+    "Finally!",
+    "Lead the way!",
+];
+const LOST_TARGET_LINES: [&str; 5] = [
+    "Must've been the wind",
+    "Where'd they go?",
+    "Huh. Nothing.",
+    "I'll get you next time",
+    "Back to my post...",
+];
+
+#[derive(Copy, Clone, Debug)]
+pub enum Bark {
+    Alerted,
+    Defection,
+    FoundCorpse,
+    Freed,
+    LostTarget,
+}
+
+impl Bark {
+    fn lines(self) -> &'static [&'static str] {
+        match self {
+            Bark::Alerted => &ALERTED_LINES,
+            Bark::Defection => &DEFECTION_LINES,
+            Bark::FoundCorpse => &FOUND_CORPSE_LINES,
+            Bark::Freed => &FREED_LINES,
+            Bark::LostTarget => &LOST_TARGET_LINES,
+        }
+    }
+
+    pub fn random_line(self, mut rng: impl Rng) -> &'static str {
+        self.lines().choose(&mut rng).unwrap()
+    }
+}
+
+#[derive(Event)]
+pub struct BarkEvent {
+    pub actor: Entity,
+    pub bark: Bark,
+}
+
+fn spawn_barks(
+    mut commands: Commands,
+    mut despawn: ResMut<DespawnSet>,
+    mut bark_events: EventReader<BarkEvent>,
+    children_query: Query<&Children>,
+    bubble_query: Query<(), With<SpeechBubble>>,
+) {
+    let mut rng = thread_rng();
+
+    for &BarkEvent { actor, bark } in bark_events.read() {
+        // Replace the actor's current speech bubble, if any
+        for &child in children_query.get(actor).into_iter().flatten() {
+            if bubble_query.contains(child) {
+                despawn.recursive(child);
+            }
+        }
+
+        let bubble = SpeechBubbleTemplate {
+            offset: Transform::from_xyz(0.0, 50.0, 0.0),
+            text: bark.random_line(&mut rng).to_string(),
+            ..default()
+        }
+        .spawn(&mut commands);
+        commands.entity(actor).add_child(bubble);
+    }
+}
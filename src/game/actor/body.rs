@@ -11,13 +11,23 @@ use crate::common::PostTransformSet;
 use crate::common::UpdateSet;
 use crate::game::actor::intent::ActorIntent;
 use crate::game::actor::player::PlayerControl;
+use crate::game::combat::weapon::AttackEvent;
+use crate::game::combat::weapon::Weapon;
+use crate::util::animation::frame::AsepriteSheet;
+use crate::util::animation::frame::FrameAnimation;
+use crate::util::animation::frame::FrameClip;
 use crate::util::animation::offset::Offset;
+use crate::util::animation::tween::Ease;
+use crate::util::animation::tween::RotationLens;
+use crate::util::animation::tween::TranslationLens;
+use crate::util::animation::tween::Tween;
 
 pub struct BodyPlugin;
 
 impl Plugin for BodyPlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<Body>();
+        app.register_type::<Body>()
+            .add_systems(Update, select_frame_clips.in_set(UpdateSet::End));
 
         app.register_type::<WalkAnimation>()
             .add_systems(
@@ -33,19 +43,10 @@ impl Plugin for BodyPlugin {
                 apply_walk_animation.in_set(PostTransformSet::Blend),
             );
 
-        app.register_type::<AttackAnimation>()
-            .add_systems(
-                Update,
-                (
-                    update_attack_animation.in_set(UpdateSet::Start),
-                    trigger_attack_animation.in_set(UpdateSet::ApplyIntents),
-                )
-                    .chain(),
-            )
-            .add_systems(
-                PostUpdate,
-                apply_attack_animation.in_set(PostTransformSet::Blend),
-            );
+        app.register_type::<AttackAnimation>().add_systems(
+            Update,
+            trigger_attack_animation.in_set(UpdateSet::HandleEvents),
+        );
 
         app.register_type::<FlinchAnimation>()
             .add_systems(Update, update_flinch_animation.in_set(UpdateSet::Start))
@@ -55,11 +56,7 @@ impl Plugin for BodyPlugin {
             );
 
         app.register_type::<DeathAnimation>()
-            .add_systems(Update, update_death_animation.in_set(UpdateSet::Start))
-            .add_systems(
-                PostUpdate,
-                apply_death_animation.in_set(PostTransformSet::Blend),
-            );
+            .add_systems(Update, trigger_death_animation.in_set(UpdateSet::End));
     }
 }
 
@@ -71,6 +68,8 @@ pub struct BodyTemplate {
     pub offset: Transform,
     pub step_sound: Option<Handle<AudioSource>>,
     pub is_corpse: bool,
+    /// Plays frame animations from this sheet, with `texture` as its image
+    pub sheet: Option<Handle<AsepriteSheet>>,
 }
 
 impl BodyTemplate {
@@ -94,13 +93,48 @@ impl BodyTemplate {
             .id();
 
         if self.is_corpse {
-            commands.entity(body).insert(DeathAnimation::default());
+            commands.entity(body).insert(DeathAnimation {
+                settled: true,
+                ..default()
+            });
+        }
+        if let Some(sheet) = self.sheet {
+            commands.entity(body).insert(FrameAnimation::new(sheet));
         }
 
         body
     }
 }
 
+/// Play the frame clip matching whichever procedural animation is active
+fn select_frame_clips(
+    mut body_query: Query<
+        (
+            &mut FrameAnimation,
+            Option<&WalkAnimation>,
+            Option<&FlinchAnimation>,
+            Option<&Tween<TranslationLens>>,
+            Has<DeathAnimation>,
+        ),
+        With<Body>,
+    >,
+) {
+    for (mut frames, walk, flinch, attack, is_dead) in &mut body_query {
+        let clip = if is_dead {
+            FrameClip::Die
+        } else if flinch.is_some_and(|x| x.t < 1.0) {
+            FrameClip::Hurt
+        } else if attack.is_some_and(|x| !x.is_finished()) {
+            FrameClip::Attack
+        } else if walk.is_some_and(|x| x.t < 1.0) {
+            FrameClip::Walk
+        } else {
+            FrameClip::Idle
+        };
+        frames.play(clip);
+    }
+}
+
 #[derive(Component, Reflect)]
 pub struct WalkAnimation {
     pub air_time: f32,
@@ -193,13 +227,12 @@ fn apply_walk_animation(mut animation_query: Query<(&WalkAnimation, &mut Transfo
     }
 }
 
+/// Lunges toward the attack direction, played as a `Tween<TranslationLens>`
 #[derive(Component, Reflect)]
 pub struct AttackAnimation {
     pub duration: f32,
     pub distance: f32,
-    pub direction: Vec2,
     pub x_sign: f32,
-    pub t: f32,
 }
 
 impl Default for AttackAnimation {
@@ -207,45 +240,48 @@ impl Default for AttackAnimation {
         Self {
             duration: 0.2,
             distance: 16.0,
-            direction: Vec2::ZERO,
             x_sign: 0.0,
-            t: 1.0,
         }
     }
 }
 
 fn trigger_attack_animation(
-    mut animation_query: Query<(&mut AttackAnimation, &Parent)>,
-    intent_query: Query<&ActorIntent>,
+    mut commands: Commands,
+    mut attack_events: EventReader<AttackEvent>,
+    mut animation_query: Query<&mut AttackAnimation>,
+    actor_query: Query<(&Children, Option<&Weapon>)>,
 ) {
-    for (mut anim, parent) in &mut animation_query {
-        let Ok(intent) = intent_query.get(parent.get()) else {
+    for &AttackEvent { actor, direction } in attack_events.read() {
+        let Ok((children, weapon)) = actor_query.get(actor) else {
             continue;
         };
-        let Some(attack) = intent.attack else {
-            continue;
-        };
-
-        anim.t = 0.0;
-        anim.direction = vec2(attack.x.abs(), attack.y);
-        anim.x_sign = attack.x.signum();
-    }
-}
-
-fn update_attack_animation(mut animation_query: Query<&mut AttackAnimation>, time: Res<Time>) {
-    let dt = time.delta_seconds();
-
-    for mut anim in &mut animation_query {
-        anim.t = (anim.t + dt / anim.duration).min(1.0);
-    }
-}
 
-fn apply_attack_animation(mut animation_query: Query<(&AttackAnimation, &mut Transform)>) {
-    for (anim, mut transform) in &mut animation_query {
-        transform.translation += (anim.direction * anim.distance * (1.0 - anim.t)).extend(0.0);
+        for &child in children {
+            let Ok(mut anim) = animation_query.get_mut(child) else {
+                continue;
+            };
+
+            if let Some(weapon) = weapon {
+                anim.duration = weapon.lunge_duration;
+                anim.distance = weapon.lunge_distance;
+            }
+            anim.x_sign = direction.x.signum();
+
+            let lunge = vec2(direction.x.abs(), direction.y) * anim.distance;
+            commands.entity(child).insert(Tween::new(
+                TranslationLens {
+                    start: lunge.extend(0.0),
+                    end: Vec3::ZERO,
+                },
+                anim.duration,
+                Ease::Linear,
+            ));
+        }
     }
 }
 
+/// Recoils away from a hit. This stays procedural rather than a `Tween<TranslationLens>`,
+/// because a flinch can interrupt an attack lunge, which owns the body's translation tween.
 #[derive(Component, Reflect)]
 pub struct FlinchAnimation {
     pub duration: f32,
@@ -291,14 +327,15 @@ fn apply_flinch_animation(mut animation_query: Query<(&FlinchAnimation, &mut Tra
     }
 }
 
+/// Hops up and topples over, played as a `Tween<TranslationLens>` and a `Tween<RotationLens>`
 #[derive(Component, Reflect)]
 pub struct DeathAnimation {
     pub height: f32,
     pub final_height: f32,
     pub air_time: f32,
     pub rotate_time: f32,
-    pub air_t: f32,
-    pub rot_t: f32,
+    /// Start in the final pose, for bodies that were already dead
+    pub settled: bool,
 }
 
 impl Default for DeathAnimation {
@@ -308,25 +345,47 @@ impl Default for DeathAnimation {
             height: 16.0,
             final_height: -8.0,
             rotate_time: 0.3,
-            air_t: 1.0,
-            rot_t: 1.0,
+            settled: false,
         }
     }
 }
 
-fn update_death_animation(mut animation_query: Query<&mut DeathAnimation>, time: Res<Time>) {
-    let dt = time.delta_seconds();
-
-    for mut anim in &mut animation_query {
-        anim.air_t = (anim.air_t + dt / anim.air_time).min(1.0);
-        anim.rot_t = (anim.rot_t + dt / anim.rotate_time).min(1.0);
-    }
-}
+fn trigger_death_animation(
+    mut commands: Commands,
+    animation_query: Query<(Entity, &DeathAnimation), Added<DeathAnimation>>,
+) {
+    for (entity, anim) in &animation_query {
+        // Replaces any attack lunge in progress
+        let peak = Vec3::Y * (anim.height + anim.final_height / 2.0);
+        let mut hop = Tween::new(
+            TranslationLens {
+                start: Vec3::ZERO,
+                end: peak,
+            },
+            anim.air_time / 2.0,
+            Ease::OutQuad,
+        )
+        .then(
+            TranslationLens {
+                start: peak,
+                end: Vec3::Y * anim.final_height,
+            },
+            anim.air_time / 2.0,
+            Ease::InQuad,
+        );
+        let mut topple = Tween::new(
+            RotationLens {
+                start: 0.0,
+                end: TAU / 4.0,
+            },
+            anim.rotate_time,
+            Ease::OutSine,
+        );
+        if anim.settled {
+            hop = hop.skip_to_end();
+            topple = topple.skip_to_end();
+        }
 
-fn apply_death_animation(mut animation_query: Query<(&DeathAnimation, &mut Transform)>) {
-    for (anim, mut transform) in &mut animation_query {
-        transform.translation.y +=
-            anim.height * (anim.air_t * PI).sin() + anim.final_height * anim.air_t;
-        transform.rotation *= Quat::from_rotation_z((anim.rot_t * TAU / 4.0).sin() * TAU / 4.0);
+        commands.entity(entity).insert((hop, topple));
     }
 }
@@ -1,26 +1,50 @@
 use bevy::math::vec2;
 use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::FieldValue;
 use bevy_kira_audio::prelude::*;
 use bevy_rapier2d::prelude::*;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 use rand::Rng;
+use strum::EnumIter;
+use strum::IntoEnumIterator;
 
 use crate::common::UpdateSet;
+use crate::game::actor::bark::Bark;
+use crate::game::actor::bark::BarkEvent;
 use crate::game::actor::body::BodyTemplate;
 use crate::game::actor::health::Health;
 use crate::game::actor::intent::ActorIntent;
 use crate::game::actor::player::PlayerControl;
+use crate::game::actor::player::Playthrough;
 use crate::game::actor::Actor;
 use crate::game::actor::ActorAssets;
 use crate::game::actor::ActorBundle;
 use crate::game::alarm::Alarm;
+use crate::game::combat::weapon::Weapon;
+use crate::game::combat::CombatAssets;
 use crate::game::combat::DeathEffects;
 use crate::game::combat::Faction;
 use crate::game::combat::HitEvent;
 use crate::game::combat::HurtEffects;
+use crate::game::combat::Resistances;
+use crate::game::combat::COLLISION_GROUP;
+use crate::game::level::field::enum_value;
+use crate::game::level::field::FieldError;
+use crate::game::level::field::FromField;
+use crate::game::level::hiding_spot::Hidden;
+use crate::game::level::registry::register_entity_spawner;
+use crate::game::level::registry::FromLevelEntity;
+use crate::game::level::registry::LevelEntity;
+use crate::game::level::signal::propagate_signals;
+use crate::game::level::signal::DeathSignal;
+use crate::game::level::signal::LogicOp;
+use crate::game::level::signal::Signal;
+use crate::game::level::signal::SignalLogic;
+use crate::util::animation::facing::Facing;
 use crate::util::ui::health_bar::HealthBarTemplate;
 use crate::util::ui::nametag::NametagTemplate;
+use crate::util::ui::status_icons::StatusIconsTemplate;
 use crate::util::vfx::AlertPopupTemplate;
 use crate::util::vfx::DropShadowTemplate;
 use crate::util::vfx::VfxAssets;
@@ -39,6 +63,39 @@ impl Plugin for EnemyPlugin {
 
         app.add_event::<AlertEvent>()
             .add_systems(Update, detect_alert_events.in_set(UpdateSet::Start));
+
+        app.register_type::<DiscoverEffects>()
+            .register_type::<Discovered>()
+            .add_systems(Update, discover_corpses.in_set(UpdateSet::Update));
+
+        for archetype in EnemyArchetype::iter() {
+            register_entity_spawner(app, archetype.identifier(), |commands, world, spawn| {
+                Ok(EnemyTemplate::from_level_entity(spawn)?.spawn(
+                    commands,
+                    world.resource::<ActorAssets>(),
+                    world.resource::<CombatAssets>(),
+                    world.resource::<VfxAssets>(),
+                ))
+            });
+        }
+        register_entity_spawner(app, "corpse", |commands, world, spawn| {
+            Ok(EnemyTemplate::from_level_entity(spawn)?.dead().spawn(
+                commands,
+                world.resource::<ActorAssets>(),
+                world.resource::<CombatAssets>(),
+                world.resource::<VfxAssets>(),
+            ))
+        });
+
+        app.register_type::<EnemySpawner>().add_systems(
+            Update,
+            fire_enemy_spawners
+                .in_set(UpdateSet::Update)
+                .after(propagate_signals),
+        );
+        register_entity_spawner(app, "spawner", |commands, _, spawn| {
+            Ok(EnemySpawnerTemplate::from_level_entity(spawn)?.spawn(commands))
+        });
     }
 }
 
@@ -106,7 +163,7 @@ fn random_fantasy_name(mut rng: impl Rng) -> String {
     )
 }
 
-fn random_name(mut rng: impl Rng) -> String {
+pub fn random_name(mut rng: impl Rng) -> String {
     if rng.gen_ratio(80, 100) {
         random_fantasy_name(rng)
     } else {
@@ -114,13 +171,131 @@ fn random_name(mut rng: impl Rng) -> String {
     }
 }
 
+#[derive(Reflect, Copy, Clone, Default, Debug, EnumIter)]
+pub enum EnemyArchetype {
+    #[default]
+    Grunt,
+    Brute,
+    Archer,
+    Slinger,
+}
+
+impl EnemyArchetype {
+    pub fn from_identifier(identifier: &str) -> Option<Self> {
+        Self::iter().find(|x| x.identifier() == identifier)
+    }
+
+    /// The LDtk identifier for placing this archetype, also accepted by the `archetype` field
+    pub fn identifier(self) -> &'static str {
+        match self {
+            EnemyArchetype::Grunt => "enemy",
+            EnemyArchetype::Brute => "brute",
+            EnemyArchetype::Archer => "archer",
+            EnemyArchetype::Slinger => "slinger",
+        }
+    }
+
+    fn health(self) -> f32 {
+        match self {
+            EnemyArchetype::Grunt => 20.0,
+            EnemyArchetype::Brute => 40.0,
+            EnemyArchetype::Archer => 14.0,
+            EnemyArchetype::Slinger => 16.0,
+        }
+    }
+
+    fn weapon(self, combat_assets: &CombatAssets) -> Weapon {
+        match self {
+            EnemyArchetype::Grunt => Weapon::claws(combat_assets),
+            EnemyArchetype::Brute => Weapon::greataxe(combat_assets),
+            EnemyArchetype::Archer => Weapon::bow(combat_assets),
+            EnemyArchetype::Slinger => Weapon::sling(combat_assets),
+        }
+    }
+
+    fn resistances(self) -> Resistances {
+        match self {
+            EnemyArchetype::Brute => Resistances {
+                armor: 2.0,
+                pierce: 0.5,
+                ..default()
+            },
+            _ => default(),
+        }
+    }
+
+    fn texture(self, actor_assets: &ActorAssets) -> Handle<Image> {
+        match self {
+            EnemyArchetype::Grunt | EnemyArchetype::Brute => actor_assets.gnoll_red.clone(),
+            EnemyArchetype::Archer | EnemyArchetype::Slinger => actor_assets.gnoll_green.clone(),
+        }
+    }
+
+    fn body_scale(self) -> f32 {
+        match self {
+            EnemyArchetype::Brute => 1.25,
+            _ => 1.0,
+        }
+    }
+
+    /// How close the enemy will approach its target
+    fn keep_distance(self) -> f32 {
+        match self {
+            EnemyArchetype::Archer => 80.0,
+            EnemyArchetype::Slinger => 60.0,
+            _ => 0.0,
+        }
+    }
+
+    fn difficulty_curve(self) -> DifficultyCurve {
+        match self {
+            EnemyArchetype::Grunt => DifficultyCurve::default(),
+            EnemyArchetype::Brute => DifficultyCurve {
+                speed: Curve::new(45.0, 75.0),
+                attack_radius: Curve::new(26.0, 30.0),
+                attack_cooldown: Curve::new(1.6, 1.0),
+                ..default()
+            },
+            EnemyArchetype::Archer => DifficultyCurve {
+                speed: Curve::new(50.0, 80.0),
+                follow_radius: Curve::new(150.0, 600.0),
+                attack_radius: Curve::new(110.0, 150.0),
+                attack_cooldown: Curve::new(2.0, 1.2),
+                ..default()
+            },
+            EnemyArchetype::Slinger => DifficultyCurve {
+                speed: Curve::new(55.0, 85.0),
+                follow_radius: Curve::new(120.0, 580.0),
+                attack_radius: Curve::new(80.0, 110.0),
+                attack_cooldown: Curve::new(2.2, 1.4),
+                ..default()
+            },
+        }
+    }
+}
+
+impl FromField for EnemyArchetype {
+    const EXPECTED: &'static str = "an enemy, brute, archer or slinger enum";
+
+    fn from_field(value: &FieldValue) -> Option<Self> {
+        let value = enum_value(value)?.to_lowercase();
+        match value.as_str() {
+            "grunt" => Some(EnemyArchetype::Grunt),
+            x => Self::from_identifier(x),
+        }
+    }
+}
+
 pub struct EnemyTemplate {
     pub transform: Transform,
     pub name: String,
+    pub archetype: EnemyArchetype,
     pub health: f32,
+    pub facing: Facing,
     pub is_corpse: bool,
     pub hurt_increase_alarm: f32,
     pub death_increase_alarm: f32,
+    pub discover_increase_alarm: f32,
 }
 
 impl Default for EnemyTemplate {
@@ -128,10 +303,13 @@ impl Default for EnemyTemplate {
         Self {
             transform: default(),
             name: "Unnamed".to_string(),
-            health: 20.0,
+            archetype: default(),
+            health: EnemyArchetype::default().health(),
+            facing: default(),
             is_corpse: false,
             hurt_increase_alarm: 0.0025,
-            death_increase_alarm: 0.025,
+            death_increase_alarm: 0.0,
+            discover_increase_alarm: 0.025,
         }
     }
 }
@@ -142,11 +320,18 @@ impl EnemyTemplate {
         self
     }
 
+    pub fn with_archetype(mut self, archetype: EnemyArchetype) -> Self {
+        self.archetype = archetype;
+        self.health = archetype.health();
+        self
+    }
+
     pub fn dead(mut self) -> Self {
         self.is_corpse = true;
         self.health = 0.0;
         self.hurt_increase_alarm = 0.0;
         self.death_increase_alarm = 0.0;
+        self.discover_increase_alarm = 0.0;
         self
     }
 
@@ -154,16 +339,20 @@ impl EnemyTemplate {
         self,
         commands: &mut Commands,
         actor_assets: &ActorAssets,
+        combat_assets: &CombatAssets,
         vfx_assets: &VfxAssets,
     ) -> Entity {
         const FACTION: Faction = Faction::Enemy;
 
         // Children
+        let scale = self.archetype.body_scale();
         let body = BodyTemplate {
-            texture: actor_assets.gnoll_red.clone(),
-            offset: Transform::from_xyz(2.0, 11.0, 0.0),
+            texture: self.archetype.texture(actor_assets),
+            offset: Transform::from_xyz(2.0 * scale, 11.0 * scale, 0.0)
+                .with_scale(Vec2::splat(scale).extend(1.0)),
             step_sound: None,
             is_corpse: self.is_corpse,
+            sheet: Some(actor_assets.gnoll_sheet.clone()),
         }
         .spawn(commands);
         let drop_shadow = DropShadowTemplate::default().spawn(commands, vfx_assets);
@@ -176,6 +365,10 @@ impl EnemyTemplate {
             offset: Transform::from_xyz(0.0, -6.0, 0.0),
         }
         .spawn(commands);
+        let status_icons = StatusIconsTemplate {
+            offset: Transform::from_xyz(13.5, -6.0, 0.0),
+        }
+        .spawn(commands);
         let alert_sensor = AlertSensorTemplate.spawn(commands);
 
         // Parent
@@ -192,12 +385,18 @@ impl EnemyTemplate {
                 ActorBundle {
                     health: Health::full(self.health),
                     actor,
+                    facing: self.facing,
                     ..default()
                 }
                 .with_faction(FACTION),
                 ColliderMassProperties::Mass(if self.is_corpse { 25.0 } else { 1.0 }),
-                EnemyAi::default(),
-                DifficultyCurve::default(),
+                EnemyAi {
+                    keep_distance: self.archetype.keep_distance(),
+                    ..default()
+                },
+                self.archetype.difficulty_curve(),
+                self.archetype.weapon(combat_assets),
+                self.archetype.resistances(),
                 HurtEffects {
                     increase_alarm: self.hurt_increase_alarm,
                     ..default()
@@ -205,22 +404,124 @@ impl EnemyTemplate {
                 DeathEffects {
                     increase_alarm: self.death_increase_alarm,
                 },
+                DiscoverEffects {
+                    increase_alarm: self.discover_increase_alarm,
+                },
+                (Signal(false), DeathSignal),
             ))
             .add_child(body)
             .add_child(drop_shadow)
             .add_child(nametag)
             .add_child(health_bar)
+            .add_child(status_icons)
             .add_child(alert_sensor)
             .id();
 
         if self.is_corpse {
-            commands.entity(enemy).remove::<ActorIntent>();
+            // Corpses placed in the level are already known to everyone
+            commands
+                .entity(enemy)
+                .remove::<ActorIntent>()
+                .insert(Discovered);
         }
 
         enemy
     }
 }
 
+impl FromLevelEntity for EnemyTemplate {
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError> {
+        let archetype = entity.field_or(
+            "archetype",
+            EnemyArchetype::from_identifier(entity.identifier).unwrap_or_default(),
+        )?;
+        let template = Self {
+            transform: entity.transform,
+            facing: entity.field_or("facing", Facing::default())?,
+            ..default()
+        }
+        .with_archetype(archetype);
+
+        Ok(Self {
+            name: entity
+                .field("name")?
+                .unwrap_or_else(|| random_name(thread_rng())),
+            health: entity.field_or("health", template.health)?,
+            ..template
+        })
+    }
+}
+
+/// Spawns an enemy each time its signal turns on, up to `limit` enemies
+#[derive(Component, Reflect)]
+pub struct EnemySpawner {
+    pub archetype: EnemyArchetype,
+    pub limit: usize,
+    pub spawned: usize,
+}
+
+fn fire_enemy_spawners(
+    mut commands: Commands,
+    mut spawner_query: Query<(&mut EnemySpawner, &Signal, &Transform, &Parent), Changed<Signal>>,
+    actor_assets: Res<ActorAssets>,
+    combat_assets: Res<CombatAssets>,
+    vfx_assets: Res<VfxAssets>,
+) {
+    for (mut spawner, signal, transform, parent) in &mut spawner_query {
+        if !signal.0 || spawner.spawned >= spawner.limit {
+            continue;
+        }
+        spawner.spawned += 1;
+
+        let enemy = EnemyTemplate {
+            transform: *transform,
+            ..default()
+        }
+        .with_archetype(spawner.archetype)
+        .with_random_name()
+        .spawn(&mut commands, &actor_assets, &combat_assets, &vfx_assets);
+        commands.entity(enemy).set_parent(parent.get());
+    }
+}
+
+pub struct EnemySpawnerTemplate {
+    pub transform: Transform,
+    pub archetype: EnemyArchetype,
+    pub limit: usize,
+    pub op: LogicOp,
+    pub delay: f32,
+}
+
+impl EnemySpawnerTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("EnemySpawner"),
+                TransformBundle::from_transform(self.transform),
+                EnemySpawner {
+                    archetype: self.archetype,
+                    limit: self.limit,
+                    spawned: 0,
+                },
+                SignalLogic::new(self.op, self.delay),
+                Signal(false),
+            ))
+            .id()
+    }
+}
+
+impl FromLevelEntity for EnemySpawnerTemplate {
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError> {
+        Ok(Self {
+            transform: entity.transform,
+            archetype: entity.field_or("archetype", EnemyArchetype::default())?,
+            limit: entity.field_or::<i32>("limit", 1)?.max(0) as usize,
+            op: entity.field_or("op", LogicOp::default())?,
+            delay: entity.field_or("delay", 0.0)?,
+        })
+    }
+}
+
 #[derive(Reflect)]
 struct Curve {
     pub y0: f32,
@@ -285,7 +586,10 @@ struct EnemyAi {
     attack_radius: f32,
     attack_cooldown: f32,
     attack_cooldown_t: f32,
+    keep_distance: f32,
+    corpse_sight_radius: f32,
     target: Option<Entity>,
+    investigate: Option<Vec2>,
 }
 
 impl Default for EnemyAi {
@@ -295,27 +599,39 @@ impl Default for EnemyAi {
             attack_radius: 20.0,
             attack_cooldown: 1.0,
             attack_cooldown_t: 0.5,
+            keep_distance: 0.0,
+            corpse_sight_radius: 80.0,
             target: None,
+            investigate: None,
         }
     }
 }
 
 fn record_enemy_intents(
     mut commands: Commands,
-    mut enemy_query: Query<(&mut EnemyAi, &mut ActorIntent, &GlobalTransform)>,
+    mut enemy_query: Query<(
+        Entity,
+        &mut EnemyAi,
+        &mut ActorIntent,
+        &GlobalTransform,
+        &Weapon,
+    )>,
     mut detect_events: EventReader<AlertEvent>,
     mut hit_events: EventReader<HitEvent>,
+    mut bark_events: EventWriter<BarkEvent>,
     parent_query: Query<&Parent>,
     player_query: Query<Entity, With<PlayerControl>>,
-    transform_query: Query<&GlobalTransform, Without<EnemyAi>>,
+    transform_query: Query<&GlobalTransform>,
+    alive_query: Query<(), (With<Actor>, With<ActorIntent>)>,
     actor_assets: Res<ActorAssets>,
     vfx_assets: Res<VfxAssets>,
+    playthrough: Res<Playthrough>,
     time: Res<Time>,
     audio: Res<Audio>,
 ) {
     let Ok(player) = player_query.get_single() else {
         let mut rng = thread_rng();
-        for (mut enemy, mut intent, _) in &mut enemy_query {
+        for (_, mut enemy, mut intent, ..) in &mut enemy_query {
             if enemy.target.is_none() {
                 continue;
             }
@@ -341,30 +657,63 @@ fn record_enemy_intents(
         }
         .spawn(&mut commands, &vfx_assets);
         commands.entity(enemy).add_child(popup);
+
+        let bark = if target == player && playthrough.defected {
+            Bark::Defection
+        } else {
+            Bark::Alerted
+        };
+        bark_events.send(BarkEvent { actor: enemy, bark });
     };
 
     for &AlertEvent { sensor, target } in detect_events.read() {
         let Ok(parent) = parent_query.get(sensor) else {
             continue;
         };
-        if let Ok((mut enemy, ..)) = enemy_query.get_mut(parent.get()) {
+        if let Ok((_, mut enemy, ..)) = enemy_query.get_mut(parent.get()) {
             handle_detection(&mut enemy, parent.get(), target);
         }
     }
-    for &HitEvent { hurtbox, .. } in hit_events.read() {
-        if let Ok((mut enemy, ..)) = enemy_query.get_mut(hurtbox) {
-            // Assume the hitbox originated from the player
-            handle_detection(&mut enemy, hurtbox, player);
+    for &HitEvent {
+        hurtbox, attacker, ..
+    } in hit_events.read()
+    {
+        // Retaliate against the attacker
+        let Some(attacker) = attacker.filter(|&attacker| attacker != hurtbox) else {
+            continue;
+        };
+        if let Ok((_, mut enemy, ..)) = enemy_query.get_mut(hurtbox) {
+            handle_detection(&mut enemy, hurtbox, attacker);
         }
     }
 
     let dt = time.delta_seconds();
-    for (mut enemy, mut intent, actor_gt) in &mut enemy_query {
-        let Some(target) = enemy.target else { continue };
+    for (entity, mut enemy, mut intent, actor_gt, weapon) in &mut enemy_query {
+        let Some(target) = enemy.target else {
+            // Investigate a point of interest
+            if let Some(point) = enemy.investigate {
+                let point_delta = point - actor_gt.translation().xy();
+                intent.movement = if point_delta.length() <= enemy.attack_radius {
+                    enemy.investigate = None;
+                    Vec2::ZERO
+                } else {
+                    point_delta.normalize()
+                };
+            }
+            continue;
+        };
         let Ok(target_gt) = transform_query.get(target) else {
             continue;
         };
 
+        // Forget dead targets
+        if !alive_query.contains(target) {
+            enemy.target = None;
+            intent.attack = None;
+            intent.movement = Vec2::ZERO;
+            continue;
+        }
+
         intent.attack = None;
         intent.movement = Vec2::ZERO;
 
@@ -374,17 +723,25 @@ fn record_enemy_intents(
         // Give up on target
         if target_distance > enemy.follow_radius {
             enemy.target = None;
+            bark_events.send(BarkEvent {
+                actor: entity,
+                bark: Bark::LostTarget,
+            });
             continue;
         }
 
         // Move towards target
         let target_direction = target_delta.normalize();
-        intent.movement = target_direction;
+        if target_distance > enemy.keep_distance {
+            intent.movement = target_direction;
+        }
 
         // Attack target
         if target_distance <= enemy.attack_radius {
+            // Only reset the cadence once the weapon will take the attack, so a weapon cooldown
+            // longer than the AI's doesn't drop attacks and slow the enemy down
             enemy.attack_cooldown_t -= dt;
-            if enemy.attack_cooldown_t <= 0.0 {
+            if enemy.attack_cooldown_t <= 0.0 && weapon.is_ready() {
                 intent.attack = Some(target_direction);
                 enemy.attack_cooldown_t = enemy.attack_cooldown;
             }
@@ -394,6 +751,74 @@ fn record_enemy_intents(
     }
 }
 
+/// Alarm raised when a living enemy discovers this actor's corpse
+#[derive(Component, Reflect, Default)]
+pub struct DiscoverEffects {
+    pub increase_alarm: f32,
+}
+
+/// Marks a corpse that has already been discovered
+#[derive(Component, Reflect)]
+pub struct Discovered;
+
+fn discover_corpses(
+    mut commands: Commands,
+    mut enemy_query: Query<(Entity, &mut EnemyAi, &GlobalTransform), With<ActorIntent>>,
+    corpse_query: Query<
+        (Entity, &GlobalTransform, Option<&DiscoverEffects>),
+        (
+            With<EnemyAi>,
+            Without<ActorIntent>,
+            Without<Discovered>,
+            Without<Hidden>,
+        ),
+    >,
+    mut bark_events: EventWriter<BarkEvent>,
+    mut alarm: ResMut<Alarm>,
+    rapier_context: Res<RapierContext>,
+) {
+    for (corpse, corpse_gt, discover) in &corpse_query {
+        let corpse_pos = corpse_gt.translation().xy();
+
+        let Some((enemy, mut ai)) = enemy_query
+            .iter_mut()
+            .filter(|(_, ai, gt)| {
+                let pos = gt.translation().xy();
+                pos.distance(corpse_pos) <= ai.corpse_sight_radius
+                    && has_line_of_sight(&rapier_context, pos, corpse_pos)
+            })
+            .map(|(enemy, ai, _)| (enemy, ai))
+            .next()
+        else {
+            continue;
+        };
+
+        commands.entity(corpse).insert(Discovered);
+        if let Some(discover) = discover {
+            alarm.increase(discover.increase_alarm);
+        }
+
+        bark_events.send(BarkEvent {
+            actor: enemy,
+            bark: Bark::FoundCorpse,
+        });
+        if ai.target.is_none() {
+            ai.investigate = Some(corpse_pos);
+        }
+    }
+}
+
+/// Check whether the line between two points is unobstructed by walls or closed gates
+pub fn has_line_of_sight(rapier_context: &RapierContext, from: Vec2, to: Vec2) -> bool {
+    let filter = QueryFilter::only_fixed()
+        .exclude_sensors()
+        .groups(CollisionGroups::new(COLLISION_GROUP, COLLISION_GROUP));
+
+    rapier_context
+        .cast_ray(from, to - from, 1.0, true, filter)
+        .is_none()
+}
+
 #[derive(Event)]
 pub struct AlertEvent {
     pub sensor: Entity,
@@ -435,7 +860,7 @@ impl AlertSensorTemplate {
                 Collider::ball(1.0),
                 ColliderMassProperties::Mass(0.0),
                 Sensor,
-                Faction::Enemy.hitbox_groups(),
+                Faction::Enemy.sensor_groups(),
                 ActiveEvents::COLLISION_EVENTS,
                 AlertSensor,
             ))
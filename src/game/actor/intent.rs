@@ -4,7 +4,11 @@ use bevy_rapier2d::prelude::*;
 use crate::common::UpdateSet;
 use crate::game::actor::body::AttackAnimation;
 use crate::game::actor::Actor;
+use crate::game::combat::status::StatusEffects;
+use crate::game::combat::weapon::Weapon;
 use crate::util::animation::facing::Facing;
+use crate::util::animation::tween::TranslationLens;
+use crate::util::animation::tween::Tween;
 use crate::util::math::MoveTowards;
 
 pub struct IntentPlugin;
@@ -29,7 +33,7 @@ pub struct ActorIntent {
 
 fn set_actor_facing(
     mut actor_query: Query<(&ActorIntent, Option<&Children>, &mut Facing)>,
-    attack_animation_query: Query<&AttackAnimation>,
+    attack_animation_query: Query<(&AttackAnimation, &Tween<TranslationLens>)>,
 ) {
     for (intent, children, mut facing) in &mut actor_query {
         if intent.movement.x == 0.0 && intent.attack.is_none() {
@@ -44,8 +48,8 @@ fn set_actor_facing(
                     attack_animation_query
                         .get(child)
                         .ok()
-                        .filter(|anim| anim.t < 1.0)
-                        .map(|anim| anim.x_sign < 0.0)
+                        .filter(|(_, tween)| !tween.is_finished())
+                        .map(|(anim, _)| anim.x_sign < 0.0)
                 })
                 .next()
                 .unwrap_or(intent.movement.x < 0.0)
@@ -57,12 +61,18 @@ fn set_actor_facing(
     }
 }
 
-fn apply_actor_movement(
-    mut actor_query: Query<(&Actor, &mut Velocity, Option<&ActorIntent>)>,
+pub fn apply_actor_movement(
+    mut actor_query: Query<(
+        &Actor,
+        &mut Velocity,
+        Option<&ActorIntent>,
+        Option<&Weapon>,
+        Option<&StatusEffects>,
+    )>,
     time: Res<Time>,
 ) {
     let dt = time.delta_seconds();
-    for (actor, mut velocity, intent) in &mut actor_query {
+    for (actor, mut velocity, intent, weapon, status_effects) in &mut actor_query {
         let (intent_direction, intent_magnitude) = if let Some(intent) = intent {
             (
                 intent.movement.normalize_or_zero(),
@@ -72,13 +82,15 @@ fn apply_actor_movement(
             (Vec2::ZERO, 0.0)
         };
 
+        let status_factor = status_effects.map_or(1.0, StatusEffects::movement_factor);
         let acceleration = if intent_direction.dot(velocity.linvel) <= 0.0 {
             actor.brake_deceleration
         } else {
-            actor.acceleration
+            actor.acceleration * status_factor
         };
 
-        let target_velocity = intent_direction * intent_magnitude * actor.speed;
+        let speed = actor.speed * weapon.map_or(1.0, Weapon::movement_factor) * status_factor;
+        let target_velocity = intent_direction * intent_magnitude * speed;
         velocity.linvel = velocity
             .linvel
             .move_towards(target_velocity, acceleration * dt);
@@ -0,0 +1,225 @@
+use bevy::prelude::*;
+use bevy::ui::Val::*;
+
+use crate::common::theme::ThemeBorderColor;
+use crate::common::theme::ThemeColor;
+use crate::common::theme::ThemeTextColors;
+use crate::common::UpdateSet;
+use crate::game::actor::player::PlayerControl;
+use crate::game::level::pickup::Pickup;
+use crate::util::ui::backdrop::BackdropTemplate;
+use crate::util::ui::font::PIXEL_FONT_HANDLE;
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Inventory>();
+
+        app.register_type::<InventorySlot>()
+            .register_type::<InventoryIcon>()
+            .add_systems(Update, update_inventory_bar.in_set(UpdateSet::UpdateUi));
+    }
+}
+
+#[derive(Reflect, Clone, Copy, Debug)]
+pub struct ItemStack {
+    pub item: Pickup,
+    pub count: u32,
+}
+
+#[derive(Component, Reflect, Clone, Debug)]
+pub struct Inventory {
+    pub slots: Vec<Option<ItemStack>>,
+    pub max_stack: u32,
+    pub selected: usize,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self {
+            slots: vec![None; Inventory::SLOTS],
+            max_stack: 5,
+            selected: 0,
+        }
+    }
+}
+
+impl Inventory {
+    pub const SLOTS: usize = 4;
+
+    /// Add an item to a matching stack or the first empty slot.
+    /// Returns false if there's no room for it.
+    pub fn add(&mut self, item: Pickup) -> bool {
+        let max_stack = self.max_stack;
+        if let Some(stack) = self
+            .slots
+            .iter_mut()
+            .flatten()
+            .find(|stack| stack.item == item && stack.count < max_stack)
+        {
+            stack.count += 1;
+            return true;
+        }
+
+        let Some(slot) = self.slots.iter_mut().find(|slot| slot.is_none()) else {
+            return false;
+        };
+        *slot = Some(ItemStack { item, count: 1 });
+        true
+    }
+
+    /// Remove one item from the selected slot
+    pub fn take_selected(&mut self) -> Option<Pickup> {
+        let slot = self.slots.get_mut(self.selected)?;
+        let stack = slot.as_mut()?;
+        let item = stack.item;
+
+        stack.count -= 1;
+        if stack.count == 0 {
+            *slot = None;
+        }
+
+        Some(item)
+    }
+
+    pub fn cycle(&mut self, step: isize) {
+        let len = self.slots.len() as isize;
+        if len == 0 {
+            return;
+        }
+        self.selected = (self.selected as isize + step).rem_euclid(len) as usize;
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct InventorySlot(pub usize);
+
+#[derive(Component, Reflect)]
+pub struct InventoryIcon;
+
+fn update_inventory_bar(
+    player_query: Query<&Inventory, With<PlayerControl>>,
+    mut slot_query: Query<(&InventorySlot, &mut ThemeBorderColor, &Children)>,
+    mut icon_query: Query<(&mut BackgroundColor, &mut Visibility), With<InventoryIcon>>,
+    mut count_query: Query<&mut Text>,
+) {
+    let Ok(inventory) = player_query.get_single() else {
+        return;
+    };
+
+    for (slot, mut border, children) in &mut slot_query {
+        let stack = inventory.slots.get(slot.0).copied().flatten();
+
+        border.0 = if slot.0 == inventory.selected {
+            ThemeColor::Primary
+        } else {
+            ThemeColor::None
+        };
+
+        for &child in children {
+            if let Ok((mut color, mut visibility)) = icon_query.get_mut(child) {
+                if let Some(stack) = stack {
+                    color.0 = stack.item.color();
+                    *visibility = Visibility::Inherited;
+                } else {
+                    *visibility = Visibility::Hidden;
+                }
+            }
+            if let Ok(mut text) = count_query.get_mut(child) {
+                text.sections[0].value = match stack {
+                    Some(stack) if stack.count > 1 => stack.count.to_string(),
+                    _ => String::new(),
+                };
+            }
+        }
+    }
+}
+
+pub struct InventoryBarTemplate;
+
+impl InventoryBarTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        let count_style = TextStyle {
+            font: PIXEL_FONT_HANDLE,
+            font_size: 16.0,
+            ..default()
+        };
+
+        // Children
+        let slots = (0..Inventory::SLOTS)
+            .map(|i| {
+                let icon = commands
+                    .spawn((
+                        Name::new("InventorySlotIcon"),
+                        NodeBundle {
+                            style: Style {
+                                width: Percent(60.0),
+                                height: Percent(60.0),
+                                margin: UiRect::all(Auto),
+                                ..default()
+                            },
+                            visibility: Visibility::Hidden,
+                            ..default()
+                        },
+                        InventoryIcon,
+                    ))
+                    .id();
+                let count = commands
+                    .spawn((
+                        Name::new("InventorySlotCount"),
+                        TextBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                right: Percent(8.0),
+                                bottom: Percent(4.0),
+                                ..default()
+                            },
+                            text: Text::from_section("", count_style.clone()),
+                            ..default()
+                        },
+                        ThemeTextColors(vec![ThemeColor::BodyText]),
+                    ))
+                    .id();
+
+                commands
+                    .spawn((
+                        Name::new("InventorySlot"),
+                        NodeBundle {
+                            style: Style {
+                                height: Percent(100.0),
+                                aspect_ratio: Some(1.0),
+                                border: UiRect::all(Px(2.0)),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(BackdropTemplate::COLOR),
+                            ..default()
+                        },
+                        ThemeBorderColor(ThemeColor::None),
+                        InventorySlot(i),
+                    ))
+                    .add_child(icon)
+                    .add_child(count)
+                    .id()
+            })
+            .collect::<Vec<_>>();
+
+        // Parent
+        commands
+            .spawn((
+                Name::new("InventoryBar"),
+                NodeBundle {
+                    style: Style {
+                        height: Percent(12.0),
+                        flex_shrink: 0.0,
+                        margin: UiRect::all(Percent(1.0)),
+                        column_gap: Percent(0.5),
+                        ..default()
+                    },
+                    ..default()
+                },
+            ))
+            .push_children(&slots)
+            .id()
+    }
+}
@@ -5,19 +5,35 @@ use leafwing_input_manager::prelude::*;
 
 use crate::common::camera::GameCamera;
 use crate::common::UpdateSet;
+use crate::game::actor::ally::AllyAi;
+use crate::game::actor::ally::AllyOrder;
+use crate::game::actor::ally::Captive;
+use crate::game::actor::ally::Follower;
 use crate::game::actor::body::Body;
 use crate::game::actor::body::BodyTemplate;
 use crate::game::actor::health::Health;
+use crate::game::actor::intent::apply_actor_movement;
 use crate::game::actor::intent::ActorIntent;
+use crate::game::actor::inventory::Inventory;
 use crate::game::actor::Actor;
 use crate::game::actor::ActorAssets;
 use crate::game::actor::ActorBundle;
 use crate::game::alarm::Alarm;
+use crate::game::combat::status::StatusEffects;
+use crate::game::combat::weapon::Weapon;
+use crate::game::combat::CombatAssets;
+use crate::game::combat::DeathEvent;
 use crate::game::combat::Faction;
 use crate::game::combat::HurtEffects;
+use crate::game::level::field::FieldError;
 use crate::game::level::plate::Plate;
+use crate::game::level::registry::register_entity_spawner;
+use crate::game::level::registry::LevelEntity;
+use crate::game::level::signal::Lever;
+use crate::game::level::signal::Signal;
 use crate::util::ui::health_bar::HealthBarTemplate;
 use crate::util::ui::nametag::NametagTemplate;
+use crate::util::ui::status_icons::StatusIconsTemplate;
 use crate::util::vfx::DropShadowTemplate;
 use crate::util::vfx::VfxAssets;
 
@@ -30,12 +46,31 @@ impl Plugin for PlayerPlugin {
 
         app.register_type::<Playthrough>()
             .init_resource::<Playthrough>()
-            .add_systems(Update, detect_defection.in_set(UpdateSet::Start));
+            .add_systems(
+                Update,
+                (
+                    detect_defection.in_set(UpdateSet::Start),
+                    count_kills.in_set(UpdateSet::HandleEvents),
+                ),
+            );
 
         app.register_type::<PlayerControl>().add_systems(
             Update,
             record_player_intent.in_set(UpdateSet::RecordIntents),
         );
+        register_entity_spawner(app, "player", spawn_player);
+
+        app.register_type::<Dragging>().add_systems(
+            Update,
+            (
+                interact.in_set(UpdateSet::HandleActions),
+                command_allies.in_set(UpdateSet::HandleActions),
+                use_items.in_set(UpdateSet::HandleActions),
+                drag_bodies
+                    .in_set(UpdateSet::ApplyIntents)
+                    .after(apply_actor_movement),
+            ),
+        );
     }
 }
 
@@ -46,6 +81,10 @@ enum PlayerAction {
     Move,
     Aim,
     Attack,
+    Interact,
+    CommandAllies,
+    CycleItem,
+    UseItem,
 }
 
 #[derive(Resource, Reflect, Default)]
@@ -54,12 +93,32 @@ pub struct Playthrough {
     pub defected: bool,
     pub start_time: f32,
     pub health: Option<f32>,
+    pub inventory: Option<Inventory>,
+    /// The iid of the entity to spawn at in the next level
+    pub entry: Option<String>,
+    pub kills: usize,
+    /// Allies who left the last level with Sai, to spawn at the next entry
+    pub followers: Vec<Follower>,
+    /// Allies who escaped the tower with Sai
+    pub escaped_allies: usize,
+}
+
+fn count_kills(
+    mut death_events: EventReader<DeathEvent>,
+    player_query: Query<(), With<PlayerControl>>,
+    mut playthrough: ResMut<Playthrough>,
+) {
+    for &DeathEvent { killer, .. } in death_events.read() {
+        if killer.is_some_and(|killer| player_query.contains(killer)) {
+            playthrough.kills += 1;
+        }
+    }
 }
 
 fn detect_defection(
     mut collision_events: EventReader<CollisionEvent>,
     plate_query: Query<(), With<Plate>>,
-    player_query: Query<&Children, With<PlayerControl>>,
+    player_query: Query<(Entity, &Children), With<PlayerControl>>,
     mut body_query: Query<&mut Handle<Image>, With<Body>>,
     mut playthrough: ResMut<Playthrough>,
     mut alarm: ResMut<Alarm>,
@@ -69,7 +128,7 @@ fn detect_defection(
     if playthrough.defected {
         return;
     }
-    let Ok(children) = player_query.get_single() else {
+    let Ok((player, children)) = player_query.get_single() else {
         return;
     };
 
@@ -78,7 +137,10 @@ fn detect_defection(
             continue;
         };
 
-        if !plate_query.contains(entity1) && !plate_query.contains(entity2) {
+        // Only Sai stepping on a plate counts, not a body pushed onto it
+        let is_player_on_plate =
+            |plate: Entity, other: Entity| other == player && plate_query.contains(plate);
+        if !is_player_on_plate(entity1, entity2) && !is_player_on_plate(entity2, entity1) {
             continue;
         }
 
@@ -107,6 +169,7 @@ fn record_player_intent(
         &mut ActorIntent,
         &GlobalTransform,
         &PlayerControl,
+        Has<Dragging>,
     )>,
     primary_window_query: Query<&Window, With<PrimaryWindow>>,
     camera: Query<(&Camera, &GlobalTransform), With<GameCamera>>,
@@ -117,7 +180,8 @@ fn record_player_intent(
     let Ok((camera, cam_gt)) = camera.get_single() else {
         return;
     };
-    let Ok((action, mut intent, player_gt, player)) = player_query.get_single_mut() else {
+    let Ok((action, mut intent, player_gt, player, dragging)) = player_query.get_single_mut()
+    else {
         return;
     };
     if player.deny_input {
@@ -130,6 +194,9 @@ fn record_player_intent(
             intent.movement = axis_pair.xy();
         }
     }
+    if dragging {
+        intent.movement *= DRAG_SPEED_FACTOR;
+    }
 
     let mut aim = None;
     if let Some(axis_pair) = action.clamped_axis_pair(&PlayerAction::Aim) {
@@ -152,11 +219,151 @@ fn record_player_intent(
     }
 }
 
+const GRAB_RADIUS: f32 = 20.0;
+const DRAG_DISTANCE: f32 = 14.0;
+const DRAG_STIFFNESS: f32 = 12.0;
+const DRAG_SPEED_FACTOR: f32 = 0.55;
+
+/// The corpse currently being dragged by the player
+#[derive(Component, Reflect)]
+pub struct Dragging(pub Entity);
+
+/// Let go of the current body, or flip the nearest lever or grab the nearest body in reach
+fn interact(
+    mut commands: Commands,
+    player_query: Query<
+        (
+            Entity,
+            &ActionState<PlayerAction>,
+            &GlobalTransform,
+            &PlayerControl,
+            Option<&Dragging>,
+        ),
+        With<ActorIntent>,
+    >,
+    corpse_query: Query<(Entity, &GlobalTransform), (With<Actor>, Without<ActorIntent>)>,
+    mut lever_query: Query<(Entity, &GlobalTransform, &mut Signal), With<Lever>>,
+) {
+    for (player, action, player_gt, control, dragging) in &player_query {
+        if control.deny_input || !action.just_pressed(&PlayerAction::Interact) {
+            continue;
+        }
+
+        // Let go of the current body
+        if dragging.is_some() {
+            commands.entity(player).remove::<Dragging>();
+            continue;
+        }
+
+        let player_pos = player_gt.translation().xy();
+        let nearest_lever = lever_query
+            .iter()
+            .map(|(lever, gt, _)| (lever, gt.translation().xy().distance(player_pos)))
+            .filter(|&(_, distance)| distance <= GRAB_RADIUS)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        let nearest_body = corpse_query
+            .iter()
+            .map(|(body, gt)| (body, gt.translation().xy().distance(player_pos)))
+            .filter(|&(_, distance)| distance <= GRAB_RADIUS)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        match (nearest_lever, nearest_body) {
+            (Some((lever, lever_distance)), body)
+                if body.map_or(true, |(_, body_distance)| lever_distance <= body_distance) =>
+            {
+                if let Ok((_, _, mut signal)) = lever_query.get_mut(lever) {
+                    signal.0 = !signal.0;
+                }
+            },
+            (_, Some((body, _))) => {
+                commands.entity(player).insert(Dragging(body));
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Toggle every freed ally between following Sai and holding position
+fn command_allies(
+    player_query: Query<(&ActionState<PlayerAction>, &PlayerControl), With<ActorIntent>>,
+    mut ally_query: Query<&mut AllyAi, Without<Captive>>,
+) {
+    let Ok((action, control)) = player_query.get_single() else {
+        return;
+    };
+    if control.deny_input || !action.just_pressed(&PlayerAction::CommandAllies) {
+        return;
+    }
+
+    let order = if ally_query
+        .iter()
+        .any(|ally| ally.order == AllyOrder::Follow)
+    {
+        AllyOrder::Stay
+    } else {
+        AllyOrder::Follow
+    };
+    for mut ally in &mut ally_query {
+        ally.order = order;
+    }
+}
+
+fn use_items(
+    mut player_query: Query<
+        (
+            &ActionState<PlayerAction>,
+            &PlayerControl,
+            &mut Inventory,
+            &mut Health,
+            &mut StatusEffects,
+        ),
+        With<ActorIntent>,
+    >,
+    mut alarm: ResMut<Alarm>,
+) {
+    let Ok((action, control, mut inventory, mut health, mut status_effects)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+    if control.deny_input {
+        return;
+    }
+
+    if action.just_pressed(&PlayerAction::CycleItem) {
+        inventory.cycle(1);
+    }
+    if action.just_pressed(&PlayerAction::UseItem) {
+        if let Some(item) = inventory.take_selected() {
+            item.apply(Some(&mut health), Some(&mut status_effects), &mut alarm);
+        }
+    }
+}
+
+fn drag_bodies(
+    mut commands: Commands,
+    player_query: Query<(Entity, &Dragging, &GlobalTransform), With<ActorIntent>>,
+    mut body_query: Query<(&GlobalTransform, &mut Velocity), Without<ActorIntent>>,
+) {
+    for (player, &Dragging(body), player_gt) in &player_query {
+        let Ok((body_gt, mut velocity)) = body_query.get_mut(body) else {
+            commands.entity(player).remove::<Dragging>();
+            continue;
+        };
+
+        // Pull the body along like it's on a short rope
+        let delta = player_gt.translation().xy() - body_gt.translation().xy();
+        let slack = (delta.length() - DRAG_DISTANCE).max(0.0);
+        velocity.linvel = delta.normalize_or_zero() * slack * DRAG_STIFFNESS;
+    }
+}
+
 pub struct PlayerTemplate {
     pub transform: Transform,
     pub texture: Handle<Image>,
     pub current_health: f32,
     pub max_health: f32,
+    pub inventory: Inventory,
 }
 
 impl Default for PlayerTemplate {
@@ -166,6 +373,7 @@ impl Default for PlayerTemplate {
             texture: default(),
             current_health: 200.0,
             max_health: 200.0,
+            inventory: default(),
         }
     }
 }
@@ -175,6 +383,7 @@ impl PlayerTemplate {
         self,
         commands: &mut Commands,
         actor_assets: &ActorAssets,
+        combat_assets: &CombatAssets,
         vfx_assets: &VfxAssets,
     ) -> Entity {
         const FACTION: Faction = Faction::Player;
@@ -185,6 +394,7 @@ impl PlayerTemplate {
             offset: Transform::from_xyz(2.0, 11.0, 0.0),
             step_sound: Some(actor_assets.step.clone()),
             is_corpse: false,
+            sheet: Some(actor_assets.gnoll_sheet.clone()),
         }
         .spawn(commands);
         let drop_shadow = DropShadowTemplate::default().spawn(commands, vfx_assets);
@@ -197,6 +407,10 @@ impl PlayerTemplate {
             offset: Transform::from_xyz(0.0, -6.0, 0.0),
         }
         .spawn(commands);
+        let status_icons = StatusIconsTemplate {
+            offset: Transform::from_xyz(13.5, -6.0, 0.0),
+        }
+        .spawn(commands);
 
         // Parent
         commands
@@ -216,6 +430,8 @@ impl PlayerTemplate {
                 }
                 .with_faction(FACTION),
                 ColliderMassProperties::Mass(5.0),
+                Weapon::claws(combat_assets),
+                self.inventory,
                 HurtEffects {
                     sound: Some(actor_assets.hurt.clone()),
                     ..default()
@@ -228,6 +444,14 @@ impl PlayerTemplate {
                         .insert(PlayerAction::Aim, DualAxis::right_stick())
                         .insert(PlayerAction::Attack, GamepadButtonType::RightTrigger)
                         .insert(PlayerAction::Attack, MouseButton::Left)
+                        .insert(PlayerAction::Interact, KeyCode::KeyE)
+                        .insert(PlayerAction::Interact, GamepadButtonType::West)
+                        .insert(PlayerAction::CommandAllies, KeyCode::KeyQ)
+                        .insert(PlayerAction::CommandAllies, GamepadButtonType::North)
+                        .insert(PlayerAction::CycleItem, KeyCode::Tab)
+                        .insert(PlayerAction::CycleItem, GamepadButtonType::LeftTrigger)
+                        .insert(PlayerAction::UseItem, KeyCode::KeyF)
+                        .insert(PlayerAction::UseItem, GamepadButtonType::East)
                         .build(),
                     ..default()
                 },
@@ -237,6 +461,39 @@ impl PlayerTemplate {
             .add_child(drop_shadow)
             .add_child(nametag)
             .add_child(health_bar)
+            .add_child(status_icons)
             .id()
     }
 }
+
+fn spawn_player(
+    commands: &mut Commands,
+    world: &World,
+    spawn: &LevelEntity,
+) -> Result<Entity, FieldError> {
+    let actor_assets = world.resource::<ActorAssets>();
+    let playthrough = world.resource::<Playthrough>();
+
+    Ok(PlayerTemplate {
+        // Spawn at the entry chosen by the previous level's exit, if any
+        transform: playthrough
+            .entry
+            .as_ref()
+            .and_then(|iid| spawn.placement(iid))
+            .unwrap_or(spawn.transform),
+        current_health: playthrough.health.unwrap_or(200.0),
+        inventory: playthrough.inventory.clone().unwrap_or_default(),
+        texture: if playthrough.defected {
+            actor_assets.gnoll_blue.clone()
+        } else {
+            actor_assets.gnoll_red.clone()
+        },
+        ..default()
+    }
+    .spawn(
+        commands,
+        actor_assets,
+        world.resource::<CombatAssets>(),
+        world.resource::<VfxAssets>(),
+    ))
+}
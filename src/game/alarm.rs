@@ -38,6 +38,10 @@ impl Alarm {
     pub fn increase(&mut self, value: f32) {
         self.0 = (self.0 + value).min(1.0);
     }
+
+    pub fn decrease(&mut self, value: f32) {
+        self.0 = (self.0 - value).max(0.0);
+    }
 }
 
 #[derive(Component, Reflect, Default)]
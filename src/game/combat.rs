@@ -1,11 +1,14 @@
 use std::f32::consts::PI;
 
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 use bevy_kira_audio::prelude::*;
 use bevy_rapier2d::prelude::*;
 use rand::thread_rng;
 use rand::Rng;
+use serde::Deserialize;
+use serde::Serialize;
 
 use crate::common::UpdateSet;
 use crate::game::actor::body::DeathAnimation;
@@ -15,8 +18,20 @@ use crate::game::actor::health::Health;
 use crate::game::actor::intent::ActorIntent;
 use crate::game::actor::Actor;
 use crate::game::alarm::Alarm;
+use crate::game::combat::projectile::Projectile;
+use crate::game::combat::projectile::ProjectileTemplate;
+use crate::game::combat::status::StatusEffect;
+use crate::game::combat::status::StatusEffects;
+use crate::game::combat::weapon::use_weapons;
+use crate::game::combat::weapon::AttackEvent;
+use crate::game::combat::weapon::HitboxShape;
+use crate::game::combat::weapon::Weapon;
 use crate::util::DespawnSet;
 
+pub mod projectile;
+pub mod status;
+pub mod weapon;
+
 pub struct CombatPlugin;
 
 impl Plugin for CombatPlugin {
@@ -24,10 +39,21 @@ impl Plugin for CombatPlugin {
         app.register_type::<CombatAssets>()
             .init_collection::<CombatAssets>();
 
+        app.register_type::<FriendlyFire>()
+            .init_resource::<FriendlyFire>();
+
+        app.add_plugins((
+            projectile::ProjectilePlugin,
+            status::StatusPlugin,
+            weapon::WeaponPlugin,
+        ));
+
         app.add_systems(
             Update,
             (
-                spawn_attack_hitboxes.in_set(UpdateSet::ApplyIntents),
+                spawn_attack_hitboxes
+                    .in_set(UpdateSet::ApplyIntents)
+                    .after(use_weapons),
                 clean_up_hitboxes.in_set(UpdateSet::Update),
             ),
         );
@@ -36,6 +62,8 @@ impl Plugin for CombatPlugin {
             .add_systems(Update, detect_hit_events.in_set(UpdateSet::Start));
 
         app.register_type::<HitEffects>()
+            .register_type::<Resistances>()
+            .add_event::<DamageDealt>()
             .add_systems(Update, apply_hit_effects.in_set(UpdateSet::HandleEvents));
 
         app.register_type::<HurtEffects>()
@@ -61,65 +89,209 @@ pub const COLLISION_GROUP: Group = Group::GROUP_1;
 pub const HITBOX_GROUP: Group = Group::GROUP_2;
 pub const PLAYER_HURTBOX_GROUP: Group = Group::GROUP_3;
 pub const ENEMY_HURTBOX_GROUP: Group = Group::GROUP_4;
+pub const DEFECTOR_HURTBOX_GROUP: Group = Group::GROUP_5;
+pub const WILDLIFE_HURTBOX_GROUP: Group = Group::GROUP_6;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Reflect)]
+pub enum Relationship {
+    /// Attacks land and alert sensors react
+    Hostile,
+    /// Attacks land, but alert sensors ignore them
+    Neutral,
+    /// Attacks pass through, unless friendly fire is enabled
+    Allied,
+}
 
-#[derive(Copy, Clone, Debug, Reflect)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Reflect)]
 pub enum Faction {
     Player,
     Enemy,
+    /// Gnolls who have turned on their side, like Sai
+    Defector,
+    /// Creatures that don't take sides
+    Wildlife,
 }
 
+const FACTIONS: [Faction; 4] = [
+    Faction::Player,
+    Faction::Enemy,
+    Faction::Defector,
+    Faction::Wildlife,
+];
+
+// How the row faction regards the column faction, in the same order as `FACTIONS`
+const RELATIONSHIPS: [[Relationship; 4]; 4] = {
+    use Relationship::*;
+    [
+        [Allied, Hostile, Allied, Neutral],
+        [Hostile, Allied, Hostile, Neutral],
+        [Allied, Hostile, Allied, Neutral],
+        [Neutral, Neutral, Neutral, Allied],
+    ]
+};
+
 impl Faction {
-    pub fn hitbox_groups(&self) -> CollisionGroups {
+    fn index(self) -> usize {
+        match self {
+            Faction::Player => 0,
+            Faction::Enemy => 1,
+            Faction::Defector => 2,
+            Faction::Wildlife => 3,
+        }
+    }
+
+    pub fn relationship(self, other: Faction) -> Relationship {
+        RELATIONSHIPS[self.index()][other.index()]
+    }
+
+    pub fn is_hostile(self, other: Faction) -> bool {
+        self.relationship(other) == Relationship::Hostile
+    }
+
+    fn hurtbox_group(self) -> Group {
+        match self {
+            Faction::Player => PLAYER_HURTBOX_GROUP,
+            Faction::Enemy => ENEMY_HURTBOX_GROUP,
+            Faction::Defector => DEFECTOR_HURTBOX_GROUP,
+            Faction::Wildlife => WILDLIFE_HURTBOX_GROUP,
+        }
+    }
+
+    /// The combined hurtbox groups of every faction whose relationship matches the predicate
+    fn hurtbox_groups_where(self, predicate: impl Fn(Relationship) -> bool) -> Group {
+        FACTIONS
+            .into_iter()
+            .filter(|&other| predicate(self.relationship(other)))
+            .fold(Group::NONE, |groups, other| groups | other.hurtbox_group())
+    }
+
+    pub fn hitbox_groups(self, friendly_fire: bool) -> CollisionGroups {
+        CollisionGroups {
+            memberships: HITBOX_GROUP,
+            filters: self.hurtbox_groups_where(|relationship| {
+                friendly_fire || relationship != Relationship::Allied
+            }),
+        }
+    }
+
+    /// Groups for sensors that should only notice hostile actors
+    pub fn sensor_groups(self) -> CollisionGroups {
         CollisionGroups {
             memberships: HITBOX_GROUP,
-            filters: match self {
-                Faction::Player => ENEMY_HURTBOX_GROUP,
-                Faction::Enemy => PLAYER_HURTBOX_GROUP,
-            },
+            filters: self
+                .hurtbox_groups_where(|relationship| relationship == Relationship::Hostile),
         }
     }
 
-    pub fn hurtbox_groups(&self) -> CollisionGroups {
+    pub fn hurtbox_groups(self) -> CollisionGroups {
         CollisionGroups {
-            memberships: match self {
-                Faction::Player => PLAYER_HURTBOX_GROUP,
-                Faction::Enemy => ENEMY_HURTBOX_GROUP,
-            },
+            memberships: self.hurtbox_group(),
             filters: HITBOX_GROUP,
         }
     }
 }
 
+/// Whether attacks can hurt allies (who will retaliate)
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct FriendlyFire(pub bool);
+
+#[derive(Reflect, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct CombatConfig {
+    pub friendly_fire: bool,
+}
+
+impl CombatConfig {
+    pub fn apply(&self, world: &mut World) {
+        world.resource_mut::<FriendlyFire>().0 = self.friendly_fire;
+    }
+}
+
+#[derive(Reflect, Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub enum DamageType {
+    #[default]
+    Slash,
+    Blunt,
+    Pierce,
+    Environmental,
+}
+
+/// Reduces incoming damage: flat armor first, then a multiplier per damage type
+#[derive(Component, Reflect)]
+pub struct Resistances {
+    pub armor: f32,
+    pub slash: f32,
+    pub blunt: f32,
+    pub pierce: f32,
+    pub environmental: f32,
+}
+
+impl Default for Resistances {
+    fn default() -> Self {
+        Self {
+            armor: 0.0,
+            slash: 1.0,
+            blunt: 1.0,
+            pierce: 1.0,
+            environmental: 1.0,
+        }
+    }
+}
+
+impl Resistances {
+    pub fn apply(&self, damage: f32, damage_type: DamageType) -> f32 {
+        let multiplier = match damage_type {
+            DamageType::Slash => self.slash,
+            DamageType::Blunt => self.blunt,
+            DamageType::Pierce => self.pierce,
+            DamageType::Environmental => self.environmental,
+        };
+
+        (damage - self.armor).max(0.0) * multiplier
+    }
+}
+
 pub struct HitboxTemplate {
     pub position: Vec3,
     pub direction: Vec2,
-    pub radius: f32,
+    pub shape: HitboxShape,
+    pub attacker: Option<Entity>,
     pub damage: f32,
+    pub damage_type: DamageType,
     pub knockback: f32,
+    pub status_effects: Vec<StatusEffect>,
     pub faction: Faction,
+    pub friendly_fire: bool,
+    pub success_sound: Option<Handle<AudioSource>>,
+    pub failure_sound: Option<Handle<AudioSource>>,
 }
 
 impl HitboxTemplate {
-    pub fn spawn(self, commands: &mut Commands, combat_assets: &CombatAssets) -> Entity {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
         commands
             .spawn((
                 Name::new("Hitbox"),
                 TransformBundle {
                     local: Transform {
                         translation: self.position,
+                        rotation: Quat::from_rotation_z(self.direction.y.atan2(self.direction.x)),
                         ..default()
                     },
                     ..default()
                 },
-                Collider::ball(self.radius),
+                self.shape.collider(),
                 Sensor,
-                self.faction.hitbox_groups(),
+                self.faction.hitbox_groups(self.friendly_fire),
                 ActiveEvents::COLLISION_EVENTS,
                 HitEffects {
+                    attacker: self.attacker,
                     damage: self.damage,
+                    damage_type: self.damage_type,
                     knockback: self.knockback * self.direction,
-                    success_sound: Some(combat_assets.attack_hit.clone()),
-                    failure_sound: Some(combat_assets.attack_miss.clone()),
+                    status_effects: self.status_effects,
+                    success_sound: self.success_sound,
+                    failure_sound: self.failure_sound,
                     ..default()
                 },
             ))
@@ -129,28 +301,56 @@ impl HitboxTemplate {
 
 pub fn spawn_attack_hitboxes(
     mut commands: Commands,
-    combat_assets: Res<CombatAssets>,
-    actor_query: Query<(&Actor, &GlobalTransform, &ActorIntent)>,
+    mut attack_events: EventReader<AttackEvent>,
+    actor_query: Query<(&Actor, &GlobalTransform, &Weapon)>,
+    friendly_fire: Res<FriendlyFire>,
 ) {
-    for (actor, gt, intent) in &actor_query {
-        let Some(direction) = intent.attack else {
+    for &AttackEvent {
+        actor: attacker,
+        direction,
+    } in attack_events.read()
+    {
+        let Ok((actor, gt, weapon)) = actor_query.get(attacker) else {
             continue;
         };
 
+        if let Some(projectile) = &weapon.projectile {
+            ProjectileTemplate {
+                position: gt.translation() + weapon.reach * direction.extend(0.0),
+                direction,
+                projectile: projectile.clone(),
+                shape: weapon.hitbox,
+                attacker: Some(attacker),
+                damage: weapon.damage,
+                damage_type: weapon.damage_type,
+                knockback: weapon.knockback,
+                status_effects: weapon.on_hit.clone(),
+                faction: actor.faction,
+                friendly_fire: friendly_fire.0,
+                success_sound: weapon.hit_sound.clone(),
+            }
+            .spawn(&mut commands);
+            continue;
+        }
+
         // Make the hitbox offset slightly ovular
         let ovular_dir = Quat::from_rotation_x(0.5 * PI * 0.3) * direction.extend(0.0);
-        let radius = 12.0;
-        let distance = radius;
 
         HitboxTemplate {
-            position: gt.translation() + distance * ovular_dir,
+            position: gt.translation() + weapon.reach * ovular_dir,
             direction,
-            radius,
-            damage: 8.0,
-            knockback: 6.0,
+            shape: weapon.hitbox,
+            attacker: Some(attacker),
+            damage: weapon.damage,
+            damage_type: weapon.damage_type,
+            knockback: weapon.knockback,
+            status_effects: weapon.on_hit.clone(),
             faction: actor.faction,
+            friendly_fire: friendly_fire.0,
+            success_sound: weapon.hit_sound.clone(),
+            failure_sound: weapon.miss_sound.clone(),
         }
-        .spawn(&mut commands, &combat_assets);
+        .spawn(&mut commands);
     }
 }
 
@@ -158,6 +358,8 @@ pub fn spawn_attack_hitboxes(
 pub struct HitEvent {
     pub hitbox: Entity,
     pub hurtbox: Entity,
+    pub attacker: Option<Entity>,
+    pub damage_type: DamageType,
 }
 
 fn detect_hit_events(
@@ -171,12 +373,18 @@ fn detect_hit_events(
         };
 
         let mut handle_collision = |hitbox: Entity, target: Entity| {
-            if !hit_query.contains(hitbox) {
+            let Ok(hit) = hit_query.get(hitbox) else {
+                return;
+            };
+            // Attackers can't hit themselves, even with friendly fire
+            if hit.attacker == Some(target) {
                 return;
             }
             hit_events.send(HitEvent {
                 hitbox,
                 hurtbox: target,
+                attacker: hit.attacker,
+                damage_type: hit.damage_type,
             });
         };
 
@@ -187,19 +395,69 @@ fn detect_hit_events(
 
 #[derive(Component, Reflect, Default)]
 pub struct HitEffects {
+    pub attacker: Option<Entity>,
     pub damage: f32,
+    pub damage_type: DamageType,
     pub knockback: Vec2,
+    pub status_effects: Vec<StatusEffect>,
     pub success: bool,
     pub success_sound: Option<Handle<AudioSource>>,
     pub failure_sound: Option<Handle<AudioSource>>,
 }
 
+/// Sent whenever something deals damage, with the amount after resistances
+#[derive(Event)]
+pub struct DamageDealt {
+    pub attacker: Option<Entity>,
+    pub target: Entity,
+    pub amount: f32,
+    pub damage_type: DamageType,
+}
+
+/// The shared path for dealing damage, whether from hits or from status effects
+#[derive(SystemParam)]
+pub struct DamageEvents<'w> {
+    damage_events: EventWriter<'w, DamageDealt>,
+    death_events: EventWriter<'w, DeathEvent>,
+}
+
+impl DamageEvents<'_> {
+    pub fn deal(
+        &mut self,
+        target: Entity,
+        health: &mut Health,
+        resistances: Option<&Resistances>,
+        attacker: Option<Entity>,
+        damage: f32,
+        damage_type: DamageType,
+    ) {
+        let amount =
+            resistances.map_or(damage, |resistances| resistances.apply(damage, damage_type));
+        if 0.0 < health.current && health.current <= amount {
+            self.death_events.send(DeathEvent {
+                entity: target,
+                killer: attacker,
+            });
+        }
+        health.current -= amount;
+
+        self.damage_events.send(DamageDealt {
+            attacker,
+            target,
+            amount,
+            damage_type,
+        });
+    }
+}
+
 fn apply_hit_effects(
     mut hit_events: EventReader<HitEvent>,
-    mut death_events: EventWriter<DeathEvent>,
+    mut damage_events: DamageEvents,
     mut hitbox_query: Query<&mut HitEffects>,
     mut hurtbox_query: Query<(
         Option<&mut Health>,
+        Option<&Resistances>,
+        Option<&mut StatusEffects>,
         Option<&mut Velocity>,
         Option<&Children>,
     )>,
@@ -208,7 +466,13 @@ fn apply_hit_effects(
 ) {
     let mut rng = thread_rng();
 
-    for &HitEvent { hitbox, hurtbox } in hit_events.read() {
+    for &HitEvent {
+        hitbox,
+        hurtbox,
+        attacker,
+        damage_type,
+    } in hit_events.read()
+    {
         let Ok(mut hit) = hitbox_query.get_mut(hitbox) else {
             continue;
         };
@@ -221,16 +485,31 @@ fn apply_hit_effects(
         }
         hit.success = true;
 
-        let Ok((health, velocity, children)) = hurtbox_query.get_mut(hurtbox) else {
+        let Ok((health, resistances, status_effects, velocity, children)) =
+            hurtbox_query.get_mut(hurtbox)
+        else {
             continue;
         };
 
         // Damage
         if let Some(mut health) = health {
-            if 0.0 < health.current && health.current <= hit.damage {
-                death_events.send(DeathEvent(hurtbox));
+            damage_events.deal(
+                hurtbox,
+                &mut health,
+                resistances,
+                attacker,
+                hit.damage,
+                damage_type,
+            );
+        }
+
+        // Status effects
+        if let Some(mut status_effects) = status_effects {
+            for effect in &hit.status_effects {
+                let mut effect = effect.clone();
+                effect.source = attacker;
+                status_effects.add(effect);
             }
-            health.current -= hit.damage;
         }
 
         // Knockback
@@ -250,7 +529,7 @@ fn apply_hit_effects(
 
 fn clean_up_hitboxes(
     mut despawn: ResMut<DespawnSet>,
-    hitbox_query: Query<(Entity, &HitEffects)>,
+    hitbox_query: Query<(Entity, &HitEffects), Without<Projectile>>,
     audio: Res<Audio>,
 ) {
     let mut rng = thread_rng();
@@ -297,7 +576,10 @@ fn apply_hurt_effects(
 }
 
 #[derive(Event)]
-pub struct DeathEvent(pub Entity);
+pub struct DeathEvent {
+    pub entity: Entity,
+    pub killer: Option<Entity>,
+}
 
 #[derive(Component, Reflect, Default)]
 pub struct DeathEffects {
@@ -314,7 +596,7 @@ fn apply_death_effects(
     children_query: Query<&Children>,
     animation_query: Query<(), With<WalkAnimation>>, // And you can use animation_query.contains(child)
 ) {
-    for &DeathEvent(entity) in death_events.read() {
+    for &DeathEvent { entity, .. } in death_events.read() {
         // Turn into a dead body
         commands
             .entity(entity)
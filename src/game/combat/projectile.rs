@@ -0,0 +1,208 @@
+use bevy::math::vec2;
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::common::UpdateSet;
+use crate::game::combat::status::StatusEffect;
+use crate::game::combat::weapon::HitboxShape;
+use crate::game::combat::DamageType;
+use crate::game::combat::Faction;
+use crate::game::combat::HitEffects;
+use crate::game::combat::HitEvent;
+use crate::game::combat::COLLISION_GROUP;
+use crate::util::animation::lifetime::Lifetime;
+use crate::util::y_sort::YSort;
+use crate::util::DespawnSet;
+
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Projectile>().add_systems(
+            Update,
+            (
+                collide_projectiles_with_walls.in_set(UpdateSet::Update),
+                apply_projectile_hits.in_set(UpdateSet::HandleEvents),
+            ),
+        );
+    }
+}
+
+#[derive(Component, Reflect, Clone)]
+pub struct Projectile {
+    pub speed: f32,
+    pub lifetime: f32,
+    pub size: Vec2,
+    pub color: Color,
+    /// Angular velocity, for projectiles that tumble through the air
+    pub spin: f32,
+    /// How many more hurtboxes the projectile can pass through
+    pub pierce: u32,
+    /// How many more times the projectile can bounce off walls and closed gates
+    pub bounce: u32,
+    /// Speed multiplier applied on each bounce
+    pub restitution: f32,
+}
+
+impl Default for Projectile {
+    fn default() -> Self {
+        Self {
+            speed: 200.0,
+            lifetime: 1.0,
+            size: Vec2::splat(2.0),
+            color: Color::WHITE,
+            spin: 0.0,
+            pierce: 0,
+            bounce: 0,
+            restitution: 1.0,
+        }
+    }
+}
+
+impl Projectile {
+    pub fn arrow() -> Self {
+        Self {
+            speed: 220.0,
+            lifetime: 1.2,
+            size: vec2(9.0, 1.5),
+            color: Color::rgb(0.55, 0.4, 0.25),
+            ..default()
+        }
+    }
+
+    pub fn rock() -> Self {
+        Self {
+            speed: 160.0,
+            lifetime: 1.5,
+            size: vec2(3.0, 3.0),
+            color: Color::rgb(0.5, 0.5, 0.55),
+            spin: 12.0,
+            bounce: 2,
+            restitution: 0.6,
+            ..default()
+        }
+    }
+}
+
+fn collide_projectiles_with_walls(
+    mut despawn: ResMut<DespawnSet>,
+    mut projectile_query: Query<(
+        Entity,
+        &mut Projectile,
+        &mut Velocity,
+        &mut Transform,
+        &GlobalTransform,
+    )>,
+    rapier_context: Res<RapierContext>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+    let filter = QueryFilter::only_fixed()
+        .exclude_sensors()
+        .groups(CollisionGroups::new(COLLISION_GROUP, COLLISION_GROUP));
+
+    for (entity, mut projectile, mut velocity, mut transform, gt) in &mut projectile_query {
+        // Look ahead by one frame of movement
+        let Some((_, intersection)) = rapier_context.cast_ray_and_get_normal(
+            gt.translation().xy(),
+            velocity.linvel * dt,
+            1.0,
+            true,
+            filter,
+        ) else {
+            continue;
+        };
+
+        if projectile.bounce == 0 {
+            despawn.recursive(entity);
+            continue;
+        }
+        projectile.bounce -= 1;
+
+        // Reflect off the surface
+        let normal = intersection.normal;
+        let linvel = velocity.linvel;
+        velocity.linvel = (linvel - 2.0 * linvel.dot(normal) * normal) * projectile.restitution;
+        if projectile.spin == 0.0 {
+            transform.rotation = Quat::from_rotation_z(velocity.linvel.y.atan2(velocity.linvel.x));
+        }
+    }
+}
+
+fn apply_projectile_hits(
+    mut hit_events: EventReader<HitEvent>,
+    mut despawn: ResMut<DespawnSet>,
+    mut projectile_query: Query<&mut Projectile>,
+) {
+    for &HitEvent { hitbox, .. } in hit_events.read() {
+        let Ok(mut projectile) = projectile_query.get_mut(hitbox) else {
+            continue;
+        };
+
+        if projectile.pierce == 0 {
+            despawn.recursive(hitbox);
+        } else {
+            projectile.pierce -= 1;
+        }
+    }
+}
+
+pub struct ProjectileTemplate {
+    pub position: Vec3,
+    pub direction: Vec2,
+    pub projectile: Projectile,
+    pub shape: HitboxShape,
+    pub attacker: Option<Entity>,
+    pub damage: f32,
+    pub damage_type: DamageType,
+    pub knockback: f32,
+    pub status_effects: Vec<StatusEffect>,
+    pub faction: Faction,
+    pub friendly_fire: bool,
+    pub success_sound: Option<Handle<AudioSource>>,
+}
+
+impl ProjectileTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("Projectile"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: self.projectile.color,
+                        custom_size: Some(self.projectile.size),
+                        ..default()
+                    },
+                    transform: Transform {
+                        translation: self.position,
+                        rotation: Quat::from_rotation_z(self.direction.y.atan2(self.direction.x)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                self.shape.collider(),
+                Sensor,
+                self.faction.hitbox_groups(self.friendly_fire),
+                ActiveEvents::COLLISION_EVENTS,
+                RigidBody::KinematicVelocityBased,
+                Velocity {
+                    linvel: self.projectile.speed * self.direction,
+                    angvel: self.projectile.spin,
+                },
+                HitEffects {
+                    attacker: self.attacker,
+                    damage: self.damage,
+                    damage_type: self.damage_type,
+                    knockback: self.knockback * self.direction,
+                    status_effects: self.status_effects,
+                    success_sound: self.success_sound,
+                    ..default()
+                },
+                Lifetime(self.projectile.lifetime),
+                YSort,
+                self.projectile,
+            ))
+            .id()
+    }
+}
@@ -0,0 +1,242 @@
+use bevy::prelude::*;
+
+use crate::common::UpdateSet;
+use crate::game::actor::health::Health;
+use crate::game::actor::intent::ActorIntent;
+use crate::game::combat::weapon::Weapon;
+use crate::game::combat::DamageEvents;
+use crate::game::combat::DamageType;
+use crate::game::combat::Resistances;
+
+pub struct StatusPlugin;
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<StatusEffects>().add_systems(
+            Update,
+            (
+                tick_status_effects.in_set(UpdateSet::Update),
+                suppress_stunned_intents
+                    .after(UpdateSet::RecordIntents)
+                    .before(UpdateSet::ApplyIntents),
+            ),
+        );
+    }
+}
+
+#[derive(Reflect, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum StatusKind {
+    /// Reduces speed and acceleration by `magnitude` (0 to 1)
+    Slow,
+    /// Increases speed and acceleration by `magnitude`
+    Haste,
+    /// Suppresses the actor's intent entirely
+    Stun,
+    /// Deals `magnitude` slash damage per second
+    Bleed,
+    /// Deals `magnitude` environmental damage per second
+    Poison,
+}
+
+impl StatusKind {
+    pub fn color(self) -> Color {
+        match self {
+            StatusKind::Slow => Color::rgb(0.4, 0.6, 0.95),
+            StatusKind::Haste => Color::rgb(0.95, 0.95, 0.95),
+            StatusKind::Stun => Color::rgb(0.95, 0.85, 0.3),
+            StatusKind::Bleed => Color::rgb(0.8, 0.15, 0.2),
+            StatusKind::Poison => Color::rgb(0.45, 0.8, 0.3),
+        }
+    }
+
+    fn damage_type(self) -> Option<DamageType> {
+        match self {
+            StatusKind::Bleed => Some(DamageType::Slash),
+            StatusKind::Poison => Some(DamageType::Environmental),
+            _ => None,
+        }
+    }
+}
+
+/// What happens when an effect is applied to an actor already affected by the same kind
+#[derive(Reflect, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Stacking {
+    /// Reset the remaining time, keeping the stronger magnitude
+    Refresh,
+    /// Add the new duration to the remaining time
+    Extend,
+    /// Add a stack (up to a limit) and reset the remaining time
+    Intensify(u32),
+    /// Track the new effect separately
+    Independent,
+}
+
+#[derive(Reflect, Clone, Debug)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub duration: f32,
+    pub magnitude: f32,
+    pub stacking: Stacking,
+    pub source: Option<Entity>,
+    pub remaining: f32,
+    pub stacks: u32,
+    tick_t: f32,
+}
+
+impl StatusEffect {
+    pub fn new(kind: StatusKind, duration: f32, magnitude: f32, stacking: Stacking) -> Self {
+        Self {
+            kind,
+            duration,
+            magnitude,
+            stacking,
+            source: None,
+            remaining: duration,
+            stacks: 1,
+            tick_t: 0.0,
+        }
+    }
+
+    pub fn stun(duration: f32) -> Self {
+        Self::new(StatusKind::Stun, duration, 1.0, Stacking::Refresh)
+    }
+
+    pub fn slow(duration: f32, magnitude: f32) -> Self {
+        Self::new(StatusKind::Slow, duration, magnitude, Stacking::Refresh)
+    }
+
+    pub fn haste(duration: f32, magnitude: f32) -> Self {
+        Self::new(StatusKind::Haste, duration, magnitude, Stacking::Refresh)
+    }
+
+    pub fn bleed(duration: f32, dps: f32) -> Self {
+        Self::new(StatusKind::Bleed, duration, dps, Stacking::Intensify(3))
+    }
+
+    pub fn poison(duration: f32, dps: f32) -> Self {
+        Self::new(StatusKind::Poison, duration, dps, Stacking::Extend)
+    }
+
+    pub fn strength(&self) -> f32 {
+        self.magnitude * self.stacks as f32
+    }
+}
+
+#[derive(Component, Reflect, Default)]
+pub struct StatusEffects(pub Vec<StatusEffect>);
+
+impl StatusEffects {
+    pub fn add(&mut self, mut effect: StatusEffect) {
+        effect.remaining = effect.duration;
+        effect.stacks = effect.stacks.max(1);
+
+        let existing = self.0.iter_mut().find(|existing| {
+            existing.kind == effect.kind && effect.stacking != Stacking::Independent
+        });
+        let Some(existing) = existing else {
+            self.0.push(effect);
+            return;
+        };
+
+        match effect.stacking {
+            Stacking::Refresh => {
+                existing.remaining = existing.remaining.max(effect.duration);
+                existing.magnitude = existing.magnitude.max(effect.magnitude);
+            },
+            Stacking::Extend => existing.remaining += effect.duration,
+            Stacking::Intensify(max_stacks) => {
+                existing.stacks = (existing.stacks + 1).min(max_stacks);
+                existing.remaining = effect.duration;
+            },
+            Stacking::Independent => unreachable!("independent effects are never merged"),
+        }
+        existing.source = effect.source.or(existing.source);
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.0.iter().any(|effect| effect.kind == kind)
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.has(StatusKind::Stun)
+    }
+
+    /// Multiplier for the actor's speed and acceleration
+    pub fn movement_factor(&self) -> f32 {
+        self.0
+            .iter()
+            .map(|effect| match effect.kind {
+                StatusKind::Slow => (1.0 - effect.strength()).max(0.0),
+                StatusKind::Haste => 1.0 + effect.strength(),
+                _ => 1.0,
+            })
+            .product()
+    }
+}
+
+/// How often damage over time is dealt, in seconds
+const TICK_INTERVAL: f32 = 0.5;
+
+fn tick_status_effects(
+    mut damage_events: DamageEvents,
+    mut status_query: Query<(
+        Entity,
+        &mut StatusEffects,
+        Option<&mut Health>,
+        Option<&Resistances>,
+    )>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+
+    for (entity, mut status_effects, mut health, resistances) in &mut status_query {
+        for effect in &mut status_effects.0 {
+            let elapsed = dt.min(effect.remaining);
+            effect.remaining -= elapsed;
+
+            // Damage over time
+            let Some(damage_type) = effect.kind.damage_type() else {
+                continue;
+            };
+            effect.tick_t += elapsed;
+            while effect.tick_t >= TICK_INTERVAL {
+                effect.tick_t -= TICK_INTERVAL;
+
+                let Some(health) = health.as_deref_mut().filter(|health| health.current > 0.0)
+                else {
+                    break;
+                };
+                damage_events.deal(
+                    entity,
+                    health,
+                    resistances,
+                    effect.source,
+                    effect.strength() * TICK_INTERVAL,
+                    damage_type,
+                );
+            }
+        }
+
+        status_effects.0.retain(|effect| effect.remaining > 0.0);
+    }
+}
+
+fn suppress_stunned_intents(
+    mut actor_query: Query<(&StatusEffects, &mut ActorIntent, Option<&mut Weapon>)>,
+) {
+    for (status_effects, mut intent, weapon) in &mut actor_query {
+        if !status_effects.is_stunned() {
+            continue;
+        }
+
+        intent.movement = Vec2::ZERO;
+        intent.attack = None;
+        // A stun interrupts an attack that's still winding up
+        if let Some(mut weapon) = weapon {
+            if weapon.pending.is_some() {
+                weapon.pending = None;
+                weapon.windup_t = 0.0;
+            }
+        }
+    }
+}
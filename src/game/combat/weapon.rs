@@ -0,0 +1,237 @@
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::common::UpdateSet;
+use crate::game::actor::intent::ActorIntent;
+use crate::game::combat::projectile::Projectile;
+use crate::game::combat::status::StatusEffect;
+use crate::game::combat::CombatAssets;
+use crate::game::combat::DamageType;
+
+pub struct WeaponPlugin;
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Weapon>()
+            .add_event::<AttackEvent>()
+            .add_systems(
+                Update,
+                (
+                    update_weapons.in_set(UpdateSet::Start),
+                    use_weapons.in_set(UpdateSet::ApplyIntents),
+                ),
+            );
+    }
+}
+
+#[derive(Reflect, Clone, Copy)]
+pub enum HitboxShape {
+    Ball(f32),
+    /// Half-extents, with X pointing in the attack direction
+    Cuboid(Vec2),
+}
+
+impl HitboxShape {
+    pub fn collider(&self) -> Collider {
+        match *self {
+            HitboxShape::Ball(radius) => Collider::ball(radius),
+            HitboxShape::Cuboid(half_extents) => Collider::cuboid(half_extents.x, half_extents.y),
+        }
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct Weapon {
+    // Hitbox
+    pub hitbox: HitboxShape,
+    pub reach: f32,
+    pub damage: f32,
+    pub damage_type: DamageType,
+    pub knockback: f32,
+    /// Fire this projectile instead of spawning a hitbox
+    pub projectile: Option<Projectile>,
+    /// Status effects applied to whoever gets hit
+    pub on_hit: Vec<StatusEffect>,
+
+    // Timing
+    pub cooldown: f32,
+    pub windup: f32,
+    pub recovery: f32,
+    /// Movement speed multiplier while winding up or recovering
+    pub busy_movement: f32,
+
+    // Presentation
+    pub hit_sound: Option<Handle<AudioSource>>,
+    pub miss_sound: Option<Handle<AudioSource>>,
+    pub lunge_duration: f32,
+    pub lunge_distance: f32,
+
+    // State
+    pub cooldown_t: f32,
+    pub windup_t: f32,
+    pub recovery_t: f32,
+    pub pending: Option<Vec2>,
+}
+
+impl Default for Weapon {
+    fn default() -> Self {
+        Self {
+            hitbox: HitboxShape::Ball(12.0),
+            reach: 12.0,
+            damage: 8.0,
+            damage_type: DamageType::Slash,
+            knockback: 6.0,
+            projectile: None,
+            on_hit: vec![],
+            cooldown: 0.0,
+            windup: 0.0,
+            recovery: 0.0,
+            busy_movement: 1.0,
+            hit_sound: None,
+            miss_sound: None,
+            lunge_duration: 0.2,
+            lunge_distance: 16.0,
+            cooldown_t: 0.0,
+            windup_t: 0.0,
+            recovery_t: 0.0,
+            pending: None,
+        }
+    }
+}
+
+impl Weapon {
+    pub fn claws(combat_assets: &CombatAssets) -> Self {
+        Self {
+            hit_sound: Some(combat_assets.attack_hit.clone()),
+            miss_sound: Some(combat_assets.attack_miss.clone()),
+            ..default()
+        }
+    }
+
+    pub fn greataxe(combat_assets: &CombatAssets) -> Self {
+        Self {
+            hitbox: HitboxShape::Cuboid(Vec2::new(12.0, 18.0)),
+            reach: 16.0,
+            damage: 20.0,
+            knockback: 12.0,
+            on_hit: vec![StatusEffect::stun(0.5)],
+            cooldown: 1.2,
+            windup: 0.35,
+            recovery: 0.4,
+            busy_movement: 0.2,
+            hit_sound: Some(combat_assets.attack_hit.clone()),
+            miss_sound: Some(combat_assets.attack_miss.clone()),
+            lunge_duration: 0.35,
+            lunge_distance: 22.0,
+            ..default()
+        }
+    }
+
+    pub fn bow(combat_assets: &CombatAssets) -> Self {
+        Self {
+            hitbox: HitboxShape::Ball(3.0),
+            reach: 8.0,
+            damage: 6.0,
+            damage_type: DamageType::Pierce,
+            knockback: 3.0,
+            projectile: Some(Projectile::arrow()),
+            on_hit: vec![StatusEffect::bleed(3.0, 1.0)],
+            cooldown: 1.5,
+            windup: 0.4,
+            recovery: 0.2,
+            busy_movement: 0.0,
+            hit_sound: Some(combat_assets.attack_hit.clone()),
+            miss_sound: None,
+            lunge_duration: 0.15,
+            lunge_distance: -6.0,
+            ..default()
+        }
+    }
+
+    pub fn sling(combat_assets: &CombatAssets) -> Self {
+        Self {
+            hitbox: HitboxShape::Ball(2.0),
+            reach: 8.0,
+            damage: 5.0,
+            damage_type: DamageType::Blunt,
+            knockback: 5.0,
+            projectile: Some(Projectile::rock()),
+            on_hit: vec![StatusEffect::slow(1.5, 0.4)],
+            cooldown: 1.8,
+            windup: 0.3,
+            recovery: 0.2,
+            busy_movement: 0.3,
+            hit_sound: Some(combat_assets.attack_hit.clone()),
+            miss_sound: None,
+            lunge_duration: 0.2,
+            lunge_distance: 8.0,
+            ..default()
+        }
+    }
+
+    /// Whether an attack intent would start winding up right now
+    pub fn is_ready(&self) -> bool {
+        self.pending.is_none() && self.cooldown_t <= 0.0
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.pending.is_some() || self.recovery_t > 0.0
+    }
+
+    pub fn movement_factor(&self) -> f32 {
+        if self.is_busy() {
+            self.busy_movement
+        } else {
+            1.0
+        }
+    }
+}
+
+fn update_weapons(mut weapon_query: Query<&mut Weapon>, time: Res<Time>) {
+    let dt = time.delta_seconds();
+
+    for mut weapon in &mut weapon_query {
+        weapon.cooldown_t = (weapon.cooldown_t - dt).max(0.0);
+        weapon.windup_t = (weapon.windup_t - dt).max(0.0);
+        weapon.recovery_t = (weapon.recovery_t - dt).max(0.0);
+    }
+}
+
+/// Sent when an actor's weapon releases an attack
+#[derive(Event)]
+pub struct AttackEvent {
+    pub actor: Entity,
+    pub direction: Vec2,
+}
+
+pub fn use_weapons(
+    mut weapon_query: Query<(Entity, &ActorIntent, &mut Weapon)>,
+    mut attack_events: EventWriter<AttackEvent>,
+) {
+    for (entity, intent, mut weapon) in &mut weapon_query {
+        // Start winding up a new attack
+        if let Some(direction) = intent.attack {
+            if weapon.is_ready() {
+                weapon.pending = Some(direction);
+                weapon.windup_t = weapon.windup;
+                weapon.cooldown_t = weapon.cooldown;
+            }
+        }
+
+        // Release the attack once it's wound up
+        let Some(direction) = weapon.pending else {
+            continue;
+        };
+        if weapon.windup_t > 0.0 {
+            continue;
+        }
+        weapon.pending = None;
+        weapon.recovery_t = weapon.recovery;
+
+        attack_events.send(AttackEvent {
+            actor: entity,
+            direction,
+        });
+    }
+}
@@ -10,6 +10,7 @@ use crate::game::actor::intent::ActorIntent;
 use crate::game::actor::player::PlayerControl;
 use crate::game::actor::player::Playthrough;
 use crate::game::alarm::Alarm;
+use crate::game::level::transition::LevelTransition;
 use crate::game::level::victory::Victory;
 use crate::util::ui::font::PIXEL_FONT_HANDLE;
 use crate::util::ui::UiRoot;
@@ -63,10 +64,11 @@ pub struct Cutscene {
 fn update_cutscene(
     mut cutscene_query: Query<(&mut Text, &mut Cutscene)>,
     mut player_query: Query<&mut PlayerControl>,
+    transition: Res<LevelTransition>,
     time: Res<Time>,
 ) {
     if let Ok(mut player) = player_query.get_single_mut() {
-        player.deny_input = !cutscene_query.is_empty();
+        player.deny_input = !cutscene_query.is_empty() || transition.is_active();
     };
 
     let dt = time.delta_seconds();
@@ -263,11 +265,14 @@ fn show_victory_message(
     let time_t = time.elapsed_seconds() - playthrough.start_time;
     let time_score = (time_scale / time_t).round() as i32;
 
-    let score = alarm_score + health_score + time_score;
+    let ally_scale = 5_000;
+    let ally_score = ally_scale * playthrough.escaped_allies as i32;
+
+    let score = alarm_score + health_score + time_score + ally_score;
 
     let message = MessageTemplate {
             title: "You escaped!".to_string(),
-            body: format!("Alarm score: {alarm_score}\n\n\n\n\nHealth score: {health_score}\n\n\n\n\nTime score: {time_score}\n\n\n\n\nTotal score: {score}\n\n\n\n\n(press R to play again)"),
+            body: format!("Alarm score: {alarm_score}\n\n\n\n\nHealth score: {health_score}\n\n\n\n\nTime score: {time_score}\n\n\n\n\nAlly score: {ally_score}\n\n\n\n\nTotal score: {score}\n\n\n\n\n(press R to play again)"),
         }
         .spawn(&mut commands);
     commands.entity(message).set_parent(ui_root.body);
@@ -1,54 +1,163 @@
 use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_ecs_ldtk::ldtk::EntityRef;
+use bevy_ecs_ldtk::ldtk::Level;
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::common::UpdateSet;
+use crate::game::actor::ally::gather_followers;
+use crate::game::actor::ally::FollowerQuery;
 use crate::game::actor::health::Health;
+use crate::game::actor::inventory::Inventory;
 use crate::game::actor::player::PlayerControl;
 use crate::game::actor::player::Playthrough;
 use crate::game::combat::COLLISION_GROUP;
 use crate::game::combat::PLAYER_HURTBOX_GROUP;
+use crate::game::level::field::FieldError;
+use crate::game::level::floor::Floor;
+use crate::game::level::registry::register_entity_spawner;
+use crate::game::level::registry::FromLevelEntity;
+use crate::game::level::registry::LevelEntity;
+use crate::game::level::transition::LevelTransition;
+use crate::game::level::victory::Victory;
+use crate::game::level::LevelAssets;
 
 pub struct ExitPlugin;
 
 impl Plugin for ExitPlugin {
     fn build(&self, app: &mut App) {
+        app.register_type::<VisitedLevels>()
+            .init_resource::<VisitedLevels>()
+            .add_systems(Update, record_visited_level.in_set(UpdateSet::Start));
+
         app.register_type::<Exit>()
             .add_systems(Update, detect_exit.in_set(UpdateSet::Start));
+        register_entity_spawner(app, "exit", |commands, _, spawn| {
+            Ok(ExitTemplate::from_level_entity(spawn)?.spawn(commands))
+        });
     }
 }
 
-#[derive(Component, Reflect)]
-pub struct Exit;
+/// The iids of every level entered during this playthrough
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct VisitedLevels(pub HashSet<String>);
+
+fn record_visited_level(
+    level_selection: Res<LevelSelection>,
+    mut visited: ResMut<VisitedLevels>,
+    level_assets: Res<LevelAssets>,
+    projects: Res<Assets<LdtkProject>>,
+) {
+    let Some(project) = projects.get(&level_assets.main) else {
+        return;
+    };
+    let levels = &project.json_data().levels;
+    let Some(level) = current_level_index(levels, &level_selection).map(|i| &levels[i]) else {
+        return;
+    };
+    if !visited.0.contains(&level.iid) {
+        visited.0.insert(level.iid.clone());
+    }
+}
+
+fn current_level_index(levels: &[Level], level_selection: &LevelSelection) -> Option<usize> {
+    (0..levels.len()).find(|&i| level_selection.is_match(&LevelIndices::in_root(i), &levels[i]))
+}
+
+/// Where an exit leads
+#[derive(Reflect, Clone, Debug, Default)]
+pub enum ExitTarget {
+    /// The next level in the project, by index
+    #[default]
+    Next,
+    /// A level by LDtk identifier or iid
+    Level(String),
+}
+
+#[derive(Component, Reflect, Default)]
+pub struct Exit {
+    pub target: ExitTarget,
+    /// The iid of the entity to spawn the player at in the target level
+    pub entry: Option<String>,
+}
 
 fn detect_exit(
     mut collision_events: EventReader<CollisionEvent>,
-    mut level_selection: ResMut<LevelSelection>,
-    player_query: Query<&Health, With<PlayerControl>>,
+    level_selection: Res<LevelSelection>,
+    mut transition: ResMut<LevelTransition>,
+    player_query: Query<(&Health, &Inventory, &GlobalTransform), With<PlayerControl>>,
+    ally_query: FollowerQuery,
     mut playthrough: ResMut<Playthrough>,
-    exit_query: Query<(), With<Exit>>,
+    mut victory: ResMut<Victory>,
+    exit_query: Query<&Exit>,
+    floor: Option<Res<Floor>>,
+    level_assets: Res<LevelAssets>,
+    projects: Res<Assets<LdtkProject>>,
 ) {
-    let LevelSelection::Indices(idx) = *level_selection else {
+    if transition.is_active() || victory.0 {
+        return;
+    }
+    let Ok((player_health, player_inventory, player_gt)) = player_query.get_single() else {
         return;
     };
-    let Ok(player_health) = player_query.get_single() else {
+    let Some(project) = projects.get(&level_assets.main) else {
         return;
     };
+    let levels = &project.json_data().levels;
 
     for &event in collision_events.read() {
         let CollisionEvent::Started(entity1, entity2, _) = event else {
             continue;
         };
-        if exit_query.contains(entity1) || exit_query.contains(entity2) {
-            *level_selection = LevelSelection::Indices(LevelIndices::in_root(idx.level + 1));
-            playthrough.health = Some(player_health.current);
+        let Some(exit) = exit_query
+            .get(entity1)
+            .or_else(|_| exit_query.get(entity2))
+            .ok()
+        else {
+            continue;
+        };
+
+        // Procedural floors lead through their rooms in order, whatever the exit says
+        let target = match (&floor, &exit.target) {
+            (Some(floor), _) => floor
+                .next_room(&level_selection)
+                .and_then(|iid| levels.iter().find(|level| level.iid == iid)),
+            (None, ExitTarget::Next) => {
+                let Some(idx) = current_level_index(levels, &level_selection) else {
+                    continue;
+                };
+                levels.get(idx + 1)
+            },
+            (None, ExitTarget::Level(name)) => levels
+                .iter()
+                .find(|level| &level.iid == name || &level.identifier == name),
+        };
+
+        // Following allies close to Sai leave with them
+        let followers = gather_followers(player_gt.translation().xy(), &ally_query);
+
+        // Running out of levels means the tower has been escaped
+        let Some(target) = target else {
+            playthrough.escaped_allies += followers.len();
+            victory.0 = true;
             break;
-        }
+        };
+
+        transition.start(target.iid.clone());
+        playthrough.health = Some(player_health.current);
+        playthrough.inventory = Some(player_inventory.clone());
+        playthrough.entry = exit.entry.clone();
+        playthrough.followers = followers;
+        break;
     }
 }
 
 pub struct ExitTemplate {
     pub transform: Transform,
+    pub target: ExitTarget,
+    pub entry: Option<String>,
 }
 
 impl ExitTemplate {
@@ -64,8 +173,30 @@ impl ExitTemplate {
                 },
                 Sensor,
                 ActiveEvents::COLLISION_EVENTS,
-                Exit,
+                Exit {
+                    target: self.target,
+                    entry: self.entry,
+                },
             ))
             .id()
     }
 }
+
+impl FromLevelEntity for ExitTemplate {
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError> {
+        // A `target` entity reference picks both the level and the entry
+        let target = entity.field::<EntityRef>("target")?;
+        let level = entity.field::<String>("level")?;
+        let entry = entity.field::<String>("entry")?;
+
+        Ok(Self {
+            transform: entity.transform,
+            target: match (&target, level) {
+                (Some(entity_ref), _) => ExitTarget::Level(entity_ref.level_iid.clone()),
+                (None, Some(level)) => ExitTarget::Level(level),
+                (None, None) => ExitTarget::Next,
+            },
+            entry: target.map(|entity_ref| entity_ref.entity_iid).or(entry),
+        })
+    }
+}
@@ -0,0 +1,346 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use bevy::prelude::*;
+use bevy_ecs_ldtk::ldtk::EntityRef;
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::util::animation::facing::Facing;
+
+/// A field on an LDtk entity instance that has the wrong type or an unknown value
+#[derive(Debug)]
+pub struct FieldError {
+    pub identifier: String,
+    pub iid: String,
+    pub field: String,
+    pub expected: &'static str,
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "LDtk entity \"{}\" ({}): field \"{}\" should be {}",
+            self.identifier, self.iid, self.field, self.expected,
+        )
+    }
+}
+
+impl Error for FieldError {}
+
+/// A value that can be read from an LDtk field
+pub trait FromField: Sized {
+    /// Describes the expected value, for error messages
+    const EXPECTED: &'static str;
+
+    /// Returns `None` if the value has the wrong type. Null values are handled by the caller.
+    fn from_field(value: &FieldValue) -> Option<Self>;
+}
+
+/// Read a typed field from an entity instance, treating a missing field or null value as `None`
+pub fn read_field<T: FromField>(
+    instance: &EntityInstance,
+    field: &str,
+) -> Result<Option<T>, FieldError> {
+    let Some(value) = instance
+        .field_instances
+        .iter()
+        .find(|x| x.identifier == field)
+        .map(|x| &x.value)
+        .filter(|x| !is_null(x))
+    else {
+        return Ok(None);
+    };
+
+    T::from_field(value).map(Some).ok_or_else(|| FieldError {
+        identifier: instance.identifier.clone(),
+        iid: instance.iid.clone(),
+        field: field.to_string(),
+        expected: T::EXPECTED,
+    })
+}
+
+fn is_null(value: &FieldValue) -> bool {
+    matches!(
+        value,
+        FieldValue::Int(None)
+            | FieldValue::Float(None)
+            | FieldValue::String(None)
+            | FieldValue::FilePath(None)
+            | FieldValue::Enum(None)
+            | FieldValue::Tile(None)
+            | FieldValue::EntityRef(None)
+            | FieldValue::Point(None)
+    )
+}
+
+/// The string value of an enum or string field, for implementing `FromField` on enums
+pub fn enum_value(value: &FieldValue) -> Option<&str> {
+    match value {
+        FieldValue::Enum(Some(x)) | FieldValue::String(Some(x)) => Some(x),
+        _ => None,
+    }
+}
+
+impl FromField for i32 {
+    const EXPECTED: &'static str = "an integer";
+
+    fn from_field(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Int(x) => *x,
+            _ => None,
+        }
+    }
+}
+
+impl FromField for f32 {
+    const EXPECTED: &'static str = "a number";
+
+    fn from_field(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Float(x) => *x,
+            FieldValue::Int(x) => x.map(|x| x as f32),
+            _ => None,
+        }
+    }
+}
+
+impl FromField for bool {
+    const EXPECTED: &'static str = "a boolean";
+
+    fn from_field(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Bool(x) => Some(*x),
+            _ => None,
+        }
+    }
+}
+
+impl FromField for String {
+    const EXPECTED: &'static str = "a string";
+
+    fn from_field(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::String(x) | FieldValue::FilePath(x) => x.clone(),
+            _ => None,
+        }
+    }
+}
+
+impl FromField for Color {
+    const EXPECTED: &'static str = "a color";
+
+    fn from_field(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Color(x) => Some(*x),
+            _ => None,
+        }
+    }
+}
+
+/// A point in grid coordinates
+impl FromField for IVec2 {
+    const EXPECTED: &'static str = "a point";
+
+    fn from_field(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::Point(x) => *x,
+            _ => None,
+        }
+    }
+}
+
+impl FromField for EntityRef {
+    const EXPECTED: &'static str = "an entity reference";
+
+    fn from_field(value: &FieldValue) -> Option<Self> {
+        match value {
+            FieldValue::EntityRef(x) => x.clone(),
+            _ => None,
+        }
+    }
+}
+
+impl FromField for Facing {
+    const EXPECTED: &'static str = "a Left or Right enum";
+
+    fn from_field(value: &FieldValue) -> Option<Self> {
+        match enum_value(value)? {
+            "Left" | "left" => Some(Facing::Left),
+            "Right" | "right" => Some(Facing::Right),
+            _ => None,
+        }
+    }
+}
+
+/// Arrays skip null elements, but fail if any element has the wrong type
+impl<T: FromField> FromField for Vec<T> {
+    const EXPECTED: &'static str = "an array";
+
+    fn from_field(value: &FieldValue) -> Option<Self> {
+        let elements: Vec<FieldValue> = match value {
+            FieldValue::Ints(x) => x.iter().map(|&x| FieldValue::Int(x)).collect(),
+            FieldValue::Floats(x) => x.iter().map(|&x| FieldValue::Float(x)).collect(),
+            FieldValue::Bools(x) => x.iter().map(|&x| FieldValue::Bool(x)).collect(),
+            FieldValue::Strings(x) => x.iter().cloned().map(FieldValue::String).collect(),
+            FieldValue::FilePaths(x) => x.iter().cloned().map(FieldValue::FilePath).collect(),
+            FieldValue::Colors(x) => x.iter().map(|&x| FieldValue::Color(x)).collect(),
+            FieldValue::Enums(x) => x.iter().cloned().map(FieldValue::Enum).collect(),
+            FieldValue::EntityRefs(x) => x.iter().cloned().map(FieldValue::EntityRef).collect(),
+            FieldValue::Points(x) => x.iter().map(|&x| FieldValue::Point(x)).collect(),
+            _ => return None,
+        };
+
+        elements
+            .iter()
+            .filter(|x| !is_null(x))
+            .map(T::from_field)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(fields: Vec<(&str, FieldValue)>) -> EntityInstance {
+        EntityInstance {
+            identifier: "lever".to_string(),
+            iid: "6f2b5a10-c640-11ed-a037-a5c0c0acf1a4".to_string(),
+            field_instances: fields
+                .into_iter()
+                .map(|(identifier, value)| FieldInstance {
+                    identifier: identifier.to_string(),
+                    tile: None,
+                    field_instance_type: String::new(),
+                    value,
+                    def_uid: 0,
+                    real_editor_values: Vec::new(),
+                })
+                .collect(),
+            ..default()
+        }
+    }
+
+    fn entity_ref(entity_iid: &str) -> EntityRef {
+        EntityRef {
+            entity_iid: entity_iid.to_string(),
+            layer_iid: "layer".to_string(),
+            level_iid: "level".to_string(),
+            world_iid: "world".to_string(),
+        }
+    }
+
+    #[test]
+    fn missing_and_null_fields_are_none() {
+        let instance = instance(vec![
+            ("count", FieldValue::Int(None)),
+            ("name", FieldValue::String(None)),
+            ("target", FieldValue::EntityRef(None)),
+        ]);
+        assert_eq!(read_field::<i32>(&instance, "count").unwrap(), None);
+        assert_eq!(read_field::<String>(&instance, "name").unwrap(), None);
+        assert_eq!(read_field::<EntityRef>(&instance, "target").unwrap(), None);
+        assert_eq!(read_field::<f32>(&instance, "missing").unwrap(), None);
+    }
+
+    #[test]
+    fn values_are_read() {
+        let instance = instance(vec![
+            ("count", FieldValue::Int(Some(3))),
+            ("delay", FieldValue::Int(Some(2))),
+            ("on", FieldValue::Bool(true)),
+            ("facing", FieldValue::Enum(Some("Left".to_string()))),
+        ]);
+        assert_eq!(read_field::<i32>(&instance, "count").unwrap(), Some(3));
+        assert_eq!(read_field::<f32>(&instance, "delay").unwrap(), Some(2.0));
+        assert_eq!(read_field::<bool>(&instance, "on").unwrap(), Some(true));
+        assert!(matches!(
+            read_field::<Facing>(&instance, "facing").unwrap(),
+            Some(Facing::Left),
+        ));
+    }
+
+    #[test]
+    fn type_mismatch_is_an_error() {
+        let instance = instance(vec![("count", FieldValue::String(Some("3".to_string())))]);
+        let error = read_field::<i32>(&instance, "count").unwrap_err();
+        assert_eq!(error.identifier, "lever");
+        assert_eq!(error.iid, instance.iid);
+        assert_eq!(error.field, "count");
+        assert_eq!(error.expected, i32::EXPECTED);
+        assert!(error.to_string().contains(&instance.iid));
+    }
+
+    #[test]
+    fn unknown_enum_value_is_an_error() {
+        let instance = instance(vec![("facing", FieldValue::Enum(Some("Up".to_string())))]);
+        assert!(read_field::<Facing>(&instance, "facing").is_err());
+    }
+
+    #[test]
+    fn arrays_skip_nulls() {
+        let instance = instance(vec![(
+            "counts",
+            FieldValue::Ints(vec![Some(1), None, Some(3)]),
+        )]);
+        assert_eq!(
+            read_field::<Vec<i32>>(&instance, "counts").unwrap(),
+            Some(vec![1, 3]),
+        );
+    }
+
+    #[test]
+    fn arrays_fail_on_mismatched_elements() {
+        let instance = instance(vec![
+            (
+                "facings",
+                FieldValue::Enums(vec![Some("Left".to_string()), Some("Sideways".to_string())]),
+            ),
+            ("count", FieldValue::Int(Some(1))),
+        ]);
+        assert!(read_field::<Vec<Facing>>(&instance, "facings").is_err());
+        assert!(read_field::<Vec<i32>>(&instance, "count").is_err());
+    }
+
+    #[test]
+    fn colors_are_read() {
+        let color = Color::rgb_u8(255, 217, 115);
+        assert_eq!(Color::from_field(&FieldValue::Color(color)), Some(color));
+        assert_eq!(
+            Vec::<Color>::from_field(&FieldValue::Colors(vec![color, Color::BLACK])),
+            Some(vec![color, Color::BLACK]),
+        );
+        assert_eq!(
+            Color::from_field(&FieldValue::String(Some("#FFD973".to_string()))),
+            None,
+        );
+    }
+
+    #[test]
+    fn entity_refs_are_read() {
+        let instance = instance(vec![
+            ("target", FieldValue::EntityRef(Some(entity_ref("a")))),
+            (
+                "targets",
+                FieldValue::EntityRefs(vec![Some(entity_ref("b")), None, Some(entity_ref("c"))]),
+            ),
+        ]);
+        assert_eq!(
+            read_field::<EntityRef>(&instance, "target").unwrap(),
+            Some(entity_ref("a")),
+        );
+        let targets = read_field::<Vec<EntityRef>>(&instance, "targets")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            targets
+                .iter()
+                .map(|x| x.entity_iid.as_str())
+                .collect::<Vec<_>>(),
+            ["b", "c"],
+        );
+        assert!(read_field::<EntityRef>(&instance, "targets").is_err());
+    }
+}
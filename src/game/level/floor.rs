@@ -0,0 +1,241 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_ecs_ldtk::ldtk::Level;
+use bevy_ecs_ldtk::prelude::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand::SeedableRng;
+
+use crate::game::level::field::read_field;
+use crate::game::level::registry::register_entity_alias;
+use crate::game::level::LevelAssets;
+use crate::sequence::SequenceState;
+
+pub struct FloorPlugin;
+
+impl Plugin for FloorPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Floor>()
+            .add_systems(OnEnter(SequenceState::Game), generate_floor);
+
+        // In the last room of a floor, exits lead out of the tower
+        register_entity_alias(app, "exit", |world, _| {
+            let is_last_room = world
+                .get_resource::<Floor>()
+                .is_some_and(|floor| floor.is_last_room(world.resource::<LevelSelection>()));
+            Ok(Some(
+                if is_last_room { "victory" } else { "exit" }.to_string(),
+            ))
+        });
+        register_entity_alias(app, "spawn_marker", |world, instance| {
+            // Hand-made levels leave spawn markers empty
+            let Some(floor) = world.get_resource::<Floor>() else {
+                return Ok(None);
+            };
+            let pool = read_field::<Vec<String>>(instance, "pool")?
+                .filter(|pool| !pool.is_empty())
+                .unwrap_or_else(|| DEFAULT_POOL.map(str::to_string).to_vec());
+            let chance = read_field::<f32>(instance, "chance")?.unwrap_or(1.0);
+
+            let mut rng = StdRng::seed_from_u64(mix_seed(floor.seed, &instance.iid));
+            if !rng.gen_bool(chance.clamp(0.0, 1.0) as f64) {
+                return Ok(None);
+            }
+            Ok(pool.choose(&mut rng).cloned())
+        });
+    }
+}
+
+/// The most rooms a floor can have
+const FLOOR_LENGTH: usize = 5;
+/// What a spawn marker with an empty `pool` can become. Repeats make an entry more likely.
+/// Plates are left out, since a marker can't link them to the room's gates.
+const DEFAULT_POOL: [&str; 5] = ["enemy", "enemy", "brute", "archer", "slinger"];
+
+/// Play a floor of LDtk levels stitched together from this seed, instead of the hand-made levels
+#[derive(Resource)]
+pub struct FloorSeed(pub u64);
+
+/// The rooms of the current procedural floor, in order
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct Floor {
+    pub seed: u64,
+    /// Level iids
+    pub rooms: Vec<String>,
+}
+
+impl Floor {
+    /// The iid of the room after the selected one, if any
+    pub fn next_room(&self, level_selection: &LevelSelection) -> Option<&str> {
+        let LevelSelection::Iid(iid) = level_selection else {
+            return None;
+        };
+        let idx = self.rooms.iter().position(|x| x == iid.as_str())?;
+        self.rooms.get(idx + 1).map(String::as_str)
+    }
+
+    pub fn is_last_room(&self, level_selection: &LevelSelection) -> bool {
+        matches!(
+            level_selection,
+            LevelSelection::Iid(iid) if self.rooms.last().is_some_and(|x| x == iid.as_str()),
+        )
+    }
+}
+
+fn generate_floor(
+    mut commands: Commands,
+    seed: Option<Res<FloorSeed>>,
+    mut level_selection: ResMut<LevelSelection>,
+    level_assets: Res<LevelAssets>,
+    projects: Res<Assets<LdtkProject>>,
+) {
+    let Some(seed) = seed else {
+        return;
+    };
+    let Some(project) = projects.get(&level_assets.main) else {
+        return;
+    };
+
+    let rooms = project
+        .json_data()
+        .levels
+        .iter()
+        .map(Room::from_level)
+        .collect::<Vec<_>>();
+    let layout = plan_floor(seed.0, &rooms, FLOOR_LENGTH);
+    let Some(first) = layout.first() else {
+        error!("No LDtk level can be a room: each needs a player and an exit or victory");
+        return;
+    };
+    info!("Generated floor from seed {}: {layout:?}", seed.0);
+
+    *level_selection = LevelSelection::Iid(LevelIid::new(first.clone()));
+    commands.insert_resource(Floor {
+        seed: seed.0,
+        rooms: layout,
+    });
+}
+
+/// What the floor layout needs to know about an LDtk level
+struct Room {
+    iid: String,
+    has_player: bool,
+    has_exit: bool,
+    has_victory: bool,
+}
+
+impl Room {
+    fn from_level(level: &Level) -> Self {
+        let identifiers = level
+            .layer_instances
+            .iter()
+            .flatten()
+            .flat_map(|layer| &layer.entity_instances)
+            .map(|instance| instance.identifier.as_str())
+            .collect::<HashSet<_>>();
+
+        Self {
+            iid: level.iid.clone(),
+            has_player: identifiers.contains("player"),
+            has_exit: identifiers.contains("exit"),
+            has_victory: identifiers.contains("victory"),
+        }
+    }
+}
+
+/// Pick the rooms of a floor in order. Every room but the last has an exit to the next, and the
+/// last room has a victory square or exits that become victory squares, so the floor can be
+/// escaped.
+fn plan_floor(seed: u64, rooms: &[Room], length: usize) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut passages = rooms
+        .iter()
+        .filter(|room| room.has_player && room.has_exit)
+        .collect::<Vec<_>>();
+    passages.shuffle(&mut rng);
+
+    // Prefer ending in a room built around its victory square
+    let goals = rooms
+        .iter()
+        .filter(|room| room.has_player && room.has_victory && !room.has_exit)
+        .collect::<Vec<_>>();
+    let goal = goals.choose(&mut rng);
+
+    passages.truncate(length.saturating_sub(goal.is_some() as usize));
+    passages
+        .into_iter()
+        .chain(goal.copied())
+        .map(|room| room.iid.clone())
+        .collect()
+}
+
+/// A stable hash, so each spawn marker rolls the same way regardless of spawn order
+fn mix_seed(seed: u64, iid: &str) -> u64 {
+    iid.bytes().fold(seed ^ 0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room(iid: &str, has_exit: bool, has_victory: bool) -> Room {
+        Room {
+            iid: iid.to_string(),
+            has_player: true,
+            has_exit,
+            has_victory,
+        }
+    }
+
+    fn rooms() -> Vec<Room> {
+        vec![
+            room("a", true, false),
+            room("b", true, false),
+            room("c", true, false),
+            room("d", true, false),
+            room("goal", false, true),
+            room("dead_end", false, false),
+        ]
+    }
+
+    #[test]
+    fn same_seed_same_floor() {
+        let rooms = rooms();
+        for seed in 0..20 {
+            assert_eq!(plan_floor(seed, &rooms, 3), plan_floor(seed, &rooms, 3));
+        }
+    }
+
+    #[test]
+    fn floor_can_be_escaped() {
+        let rooms = rooms();
+        for seed in 0..20 {
+            let floor = plan_floor(seed, &rooms, 3);
+            assert_eq!(floor.len(), 3);
+            assert_eq!(floor.last().map(String::as_str), Some("goal"));
+            assert!(!floor.contains(&"dead_end".to_string()));
+
+            let unique = floor.iter().collect::<HashSet<_>>();
+            assert_eq!(unique.len(), floor.len());
+        }
+    }
+
+    #[test]
+    fn floor_is_limited_by_rooms() {
+        let rooms = vec![room("a", true, false), room("dead_end", false, false)];
+        assert_eq!(plan_floor(0, &rooms, 5), ["a"]);
+        assert!(plan_floor(0, &rooms[1..], 5).is_empty());
+    }
+
+    #[test]
+    fn markers_roll_independently() {
+        assert_eq!(mix_seed(7, "marker"), mix_seed(7, "marker"));
+        assert_ne!(mix_seed(7, "marker"), mix_seed(8, "marker"));
+        assert_ne!(mix_seed(7, "marker_a"), mix_seed(7, "marker_b"));
+    }
+}
@@ -2,7 +2,16 @@ use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 use bevy_rapier2d::prelude::*;
 
+use crate::common::UpdateSet;
 use crate::game::combat::COLLISION_GROUP;
+use crate::game::level::field::FieldError;
+use crate::game::level::registry::register_entity_spawner;
+use crate::game::level::registry::FromLevelEntity;
+use crate::game::level::registry::LevelEntity;
+use crate::game::level::signal::propagate_signals;
+use crate::game::level::signal::LogicOp;
+use crate::game::level::signal::Signal;
+use crate::game::level::signal::SignalLogic;
 
 pub struct GatePlugin;
 
@@ -11,7 +20,18 @@ impl Plugin for GatePlugin {
         app.register_type::<GateAssets>()
             .init_collection::<GateAssets>();
 
-        app.register_type::<Gate>();
+        app.register_type::<Gate>().add_systems(
+            Update,
+            actuate_gates
+                .in_set(UpdateSet::Update)
+                .after(propagate_signals),
+        );
+        for identifier in ["gate", "open_gate", "closed_gate"] {
+            register_entity_spawner(app, identifier, |commands, world, spawn| {
+                Ok(GateTemplate::from_level_entity(spawn)?
+                    .spawn(commands, world.resource::<GateAssets>()))
+            });
+        }
     }
 }
 
@@ -27,11 +47,38 @@ pub struct GateAssets {
 #[derive(Component, Reflect)]
 pub struct Gate {
     pub open: bool,
+    /// Whether the gate is open while its signal is off
+    pub default_open: bool,
+}
+
+/// Open or close gates whose signal flipped
+fn actuate_gates(
+    mut gate_query: Query<
+        (&mut Gate, &Signal, &mut Handle<Image>, &mut CollisionGroups),
+        Changed<Signal>,
+    >,
+    gate_assets: Res<GateAssets>,
+) {
+    for (mut gate, signal, mut image, mut groups) in &mut gate_query {
+        let open = gate.default_open != signal.0;
+        if open == gate.open {
+            continue;
+        }
+        gate.open = open;
+
+        (groups.filters, *image) = if open {
+            (Group::empty(), gate_assets.open.clone())
+        } else {
+            (COLLISION_GROUP, gate_assets.closed.clone())
+        };
+    }
 }
 
 pub struct GateTemplate {
     pub transform: Transform,
     pub open: bool,
+    pub op: LogicOp,
+    pub delay: f32,
 }
 
 impl GateTemplate {
@@ -57,8 +104,26 @@ impl GateTemplate {
                 },
                 Friction::new(0.0),
                 RigidBody::Fixed,
-                Gate { open: self.open },
+                Gate {
+                    open: self.open,
+                    default_open: self.open,
+                },
+                SignalLogic::new(self.op, self.delay),
+                Signal(false),
             ))
             .id()
     }
 }
+
+impl FromLevelEntity for GateTemplate {
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError> {
+        Ok(Self {
+            transform: entity.transform,
+            // The `open` field overrides the older `open_gate` / `closed_gate` split
+            open: entity.field_or("open", entity.identifier == "open_gate")?,
+            // Each plate or lever linked to a gate flips it, as plates always have
+            op: entity.field_or("op", LogicOp::Toggle)?,
+            delay: entity.field_or("delay", 0.0)?,
+        })
+    }
+}
@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use crate::common::UpdateSet;
+use crate::game::actor::intent::ActorIntent;
+use crate::game::actor::player::Dragging;
+use crate::game::actor::Actor;
+use crate::game::level::registry::register_entity_spawner;
+
+pub struct HidingSpotPlugin;
+
+impl Plugin for HidingSpotPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<HidingSpot>()
+            .register_type::<Hidden>()
+            .add_systems(Update, hide_bodies.in_set(UpdateSet::Update));
+        register_entity_spawner(app, "hiding_spot", |commands, _, spawn| {
+            Ok(HidingSpotTemplate {
+                transform: spawn.transform,
+                size: Vec2::new(spawn.instance.width as f32, spawn.instance.height as f32),
+            }
+            .spawn(commands))
+        });
+    }
+}
+
+/// A zone (e.g. bushes or a closet) that hides bodies dropped inside it
+#[derive(Component, Reflect)]
+pub struct HidingSpot {
+    pub half_size: Vec2,
+}
+
+/// Marks a body that enemies can't perceive
+#[derive(Component, Reflect)]
+pub struct Hidden;
+
+fn hide_bodies(
+    mut commands: Commands,
+    spot_query: Query<(&HidingSpot, &GlobalTransform)>,
+    body_query: Query<(Entity, &GlobalTransform, Has<Hidden>), (With<Actor>, Without<ActorIntent>)>,
+    dragging_query: Query<&Dragging>,
+) {
+    let dragged = dragging_query
+        .iter()
+        .map(|dragging| dragging.0)
+        .collect::<HashSet<_>>();
+
+    for (body, body_gt, was_hidden) in &body_query {
+        let pos = body_gt.translation().xy();
+        let hidden = !dragged.contains(&body)
+            && spot_query.iter().any(|(spot, spot_gt)| {
+                let delta = (pos - spot_gt.translation().xy()).abs();
+                delta.x <= spot.half_size.x && delta.y <= spot.half_size.y
+            });
+
+        if hidden && !was_hidden {
+            commands.entity(body).insert(Hidden);
+        } else if !hidden && was_hidden {
+            commands.entity(body).remove::<Hidden>();
+        }
+    }
+}
+
+pub struct HidingSpotTemplate {
+    pub transform: Transform,
+    pub size: Vec2,
+}
+
+impl HidingSpotTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("HidingSpot"),
+                TransformBundle::from_transform(self.transform),
+                HidingSpot {
+                    half_size: self.size / 2.0,
+                },
+            ))
+            .id()
+    }
+}
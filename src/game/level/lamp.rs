@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+
+use crate::common::UpdateSet;
+use crate::game::level::field::FieldError;
+use crate::game::level::registry::register_entity_spawner;
+use crate::game::level::registry::FromLevelEntity;
+use crate::game::level::registry::LevelEntity;
+use crate::game::level::signal::propagate_signals;
+use crate::game::level::signal::LogicOp;
+use crate::game::level::signal::Signal;
+use crate::game::level::signal::SignalLogic;
+use crate::util::y_sort::YSort;
+
+pub struct LampPlugin;
+
+impl Plugin for LampPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Lamp>().add_systems(
+            Update,
+            update_lamps
+                .in_set(UpdateSet::Update)
+                .after(propagate_signals),
+        );
+        register_entity_spawner(app, "light", |commands, _, spawn| {
+            Ok(LampTemplate::from_level_entity(spawn)?.spawn(commands))
+        });
+    }
+}
+
+/// A light that shines while its signal is on
+#[derive(Component, Reflect)]
+pub struct Lamp {
+    pub color: Color,
+}
+
+impl Lamp {
+    const OFF_COLOR: Color = Color::rgb(0.25, 0.22, 0.2);
+}
+
+fn update_lamps(mut lamp_query: Query<(&Lamp, &Signal, &mut Sprite), Changed<Signal>>) {
+    for (lamp, signal, mut sprite) in &mut lamp_query {
+        sprite.color = if signal.0 {
+            lamp.color
+        } else {
+            Lamp::OFF_COLOR
+        };
+    }
+}
+
+pub struct LampTemplate {
+    pub transform: Transform,
+    pub color: Color,
+    pub size: f32,
+    pub op: LogicOp,
+    pub delay: f32,
+}
+
+impl LampTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("Lamp"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Lamp::OFF_COLOR,
+                        custom_size: Some(Vec2::splat(self.size)),
+                        ..default()
+                    },
+                    transform: self.transform,
+                    ..default()
+                },
+                YSort,
+                Lamp { color: self.color },
+                SignalLogic::new(self.op, self.delay),
+                Signal(false),
+            ))
+            .id()
+    }
+}
+
+impl FromLevelEntity for LampTemplate {
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError> {
+        Ok(Self {
+            transform: entity.transform,
+            color: entity.field_or("color", Color::rgb(1.0, 0.85, 0.45))?,
+            size: entity.field_or("size", 5.0)?,
+            op: entity.field_or("op", LogicOp::default())?,
+            delay: entity.field_or("delay", 0.0)?,
+        })
+    }
+}
@@ -0,0 +1,216 @@
+use bevy::prelude::*;
+use bevy_asset_loader::prelude::*;
+use bevy_ecs_ldtk::prelude::FieldValue;
+use bevy_kira_audio::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::thread_rng;
+use rand::Rng;
+use strum::EnumIter;
+use strum::IntoEnumIterator;
+
+use crate::common::UpdateSet;
+use crate::game::actor::health::Health;
+use crate::game::actor::inventory::Inventory;
+use crate::game::alarm::Alarm;
+use crate::game::combat::status::StatusEffect;
+use crate::game::combat::status::StatusEffects;
+use crate::game::combat::status::StatusKind;
+use crate::game::combat::COLLISION_GROUP;
+use crate::game::combat::PLAYER_HURTBOX_GROUP;
+use crate::game::level::field::enum_value;
+use crate::game::level::field::FromField;
+use crate::game::level::registry::register_entity_spawner;
+use crate::util::vfx::PickupFlashTemplate;
+use crate::util::y_sort::YSort;
+use crate::util::DespawnSet;
+
+pub struct PickupPlugin;
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PickupAssets>()
+            .init_collection::<PickupAssets>();
+
+        app.register_type::<Pickup>()
+            .add_systems(Update, collect_pickups.in_set(UpdateSet::Start));
+        for pickup in Pickup::iter() {
+            register_entity_spawner(app, pickup.identifier(), move |commands, _, spawn| {
+                Ok(PickupTemplate {
+                    transform: spawn.transform,
+                    pickup,
+                }
+                .spawn(commands))
+            });
+        }
+    }
+}
+
+#[derive(AssetCollection, Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct PickupAssets {
+    #[asset(path = "sound/sfx/pop_2.wav")]
+    collect: Handle<AudioSource>,
+}
+
+#[derive(Component, Reflect, Copy, Clone, PartialEq, Eq, Debug, EnumIter)]
+pub enum Pickup {
+    /// Restores a good chunk of health
+    Food,
+    /// Restores a little health and stops bleeding
+    Bandage,
+    /// Grants a burst of speed
+    Potion,
+    /// Calms the tower down a bit
+    Incense,
+}
+
+impl Pickup {
+    pub fn from_identifier(identifier: &str) -> Option<Self> {
+        Self::iter().find(|x| x.identifier() == identifier)
+    }
+
+    pub fn identifier(self) -> &'static str {
+        match self {
+            Pickup::Food => "food",
+            Pickup::Bandage => "bandage",
+            Pickup::Potion => "potion",
+            Pickup::Incense => "incense",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Pickup::Food => Color::rgb(0.85, 0.55, 0.3),
+            Pickup::Bandage => Color::rgb(0.95, 0.95, 0.9),
+            Pickup::Potion => Color::rgb(0.4, 0.6, 0.95),
+            Pickup::Incense => Color::rgb(0.7, 0.5, 0.85),
+        }
+    }
+
+    /// Apply this pickup's effects to whoever collected it
+    pub fn apply(
+        self,
+        health: Option<&mut Health>,
+        status_effects: Option<&mut StatusEffects>,
+        alarm: &mut Alarm,
+    ) {
+        let heal = match self {
+            Pickup::Food => 40.0,
+            Pickup::Bandage => 15.0,
+            _ => 0.0,
+        };
+        if let Some(health) = health {
+            health.current = (health.current + heal).min(health.max);
+        }
+
+        if let Some(status_effects) = status_effects {
+            match self {
+                Pickup::Bandage => status_effects
+                    .0
+                    .retain(|effect| effect.kind != StatusKind::Bleed),
+                Pickup::Potion => status_effects.add(StatusEffect::haste(6.0, 0.5)),
+                _ => {},
+            }
+        }
+
+        if self == Pickup::Incense {
+            alarm.decrease(0.1);
+        }
+    }
+}
+
+impl FromField for Pickup {
+    const EXPECTED: &'static str = "a pickup identifier";
+
+    fn from_field(value: &FieldValue) -> Option<Self> {
+        Self::from_identifier(&enum_value(value)?.to_lowercase())
+    }
+}
+
+fn collect_pickups(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut despawn: ResMut<DespawnSet>,
+    mut alarm: ResMut<Alarm>,
+    pickup_query: Query<(&Pickup, &GlobalTransform)>,
+    mut collector_query: Query<(
+        Option<&mut Inventory>,
+        Option<&mut Health>,
+        Option<&mut StatusEffects>,
+    )>,
+    pickup_assets: Res<PickupAssets>,
+    audio: Res<Audio>,
+) {
+    let mut rng = thread_rng();
+
+    for &event in collision_events.read() {
+        let CollisionEvent::Started(entity1, entity2, _) = event else {
+            continue;
+        };
+
+        let mut handle_collision = |pickup_entity: Entity, collector: Entity| {
+            let Ok((&pickup, gt)) = pickup_query.get(pickup_entity) else {
+                return;
+            };
+            let Ok((inventory, health, status_effects)) = collector_query.get_mut(collector) else {
+                return;
+            };
+
+            // Store the pickup for later, or consume it right away if there's no room
+            if !inventory.is_some_and(|mut inventory| inventory.add(pickup)) {
+                pickup.apply(
+                    health.map(Mut::into_inner),
+                    status_effects.map(Mut::into_inner),
+                    &mut alarm,
+                );
+            }
+
+            audio
+                .play(pickup_assets.collect.clone())
+                .with_volume(0.5)
+                .with_playback_rate(rng.gen_range(0.9..1.1));
+            PickupFlashTemplate {
+                transform: Transform::from_translation(gt.translation()),
+                color: pickup.color(),
+            }
+            .spawn(&mut commands);
+            despawn.recursive(pickup_entity);
+        };
+
+        handle_collision(entity1, entity2);
+        handle_collision(entity2, entity1);
+    }
+}
+
+pub struct PickupTemplate {
+    pub transform: Transform,
+    pub pickup: Pickup,
+}
+
+impl PickupTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new(format!("Pickup{:?}", self.pickup)),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: self.pickup.color(),
+                        custom_size: Some(Vec2::splat(5.0)),
+                        ..default()
+                    },
+                    transform: self.transform,
+                    ..default()
+                },
+                Collider::ball(4.0),
+                CollisionGroups {
+                    memberships: COLLISION_GROUP,
+                    filters: PLAYER_HURTBOX_GROUP,
+                },
+                Sensor,
+                ActiveEvents::COLLISION_EVENTS,
+                YSort,
+                self.pickup,
+            ))
+            .id()
+    }
+}
@@ -5,9 +5,11 @@ use bevy_rapier2d::prelude::*;
 
 use crate::common::UpdateSet;
 use crate::game::combat::COLLISION_GROUP;
-use crate::game::combat::PLAYER_HURTBOX_GROUP;
-use crate::game::level::gate::Gate;
-use crate::game::level::gate::GateAssets;
+use crate::game::level::field::FieldError;
+use crate::game::level::registry::register_entity_spawner;
+use crate::game::level::registry::FromLevelEntity;
+use crate::game::level::registry::LevelEntity;
+use crate::game::level::signal::Signal;
 
 pub struct PlatePlugin;
 
@@ -16,8 +18,16 @@ impl Plugin for PlatePlugin {
         app.register_type::<PlateAssets>()
             .init_collection::<PlateAssets>();
 
-        app.register_type::<Plate>()
-            .add_systems(Update, activate_plates.in_set(UpdateSet::Start));
+        app.register_type::<Plate>().add_systems(
+            Update,
+            (track_plate_occupants, press_plates)
+                .chain()
+                .in_set(UpdateSet::Start),
+        );
+        register_entity_spawner(app, "plate", |commands, world, spawn| {
+            Ok(PlateTemplate::from_level_entity(spawn)?
+                .spawn(commands, world.resource::<PlateAssets>()))
+        });
     }
 }
 
@@ -35,63 +45,88 @@ pub struct PlateAssets {
 
 #[derive(Component, Reflect, Default)]
 pub struct Plate {
-    gates: Vec<Entity>,
-    pressed: bool,
+    /// Whether the plate stays pressed after being stepped off
+    pub latched: bool,
+    /// The total mass needed to press the plate
+    pub threshold: f32,
+    occupants: Vec<Entity>,
 }
 
-fn activate_plates(
+fn track_plate_occupants(
     mut collision_events: EventReader<CollisionEvent>,
-    mut plate_query: Query<(&mut Plate, &mut Handle<Image>)>,
-    mut gate_query: Query<(&mut Gate, &mut Handle<Image>, &mut CollisionGroups), Without<Plate>>,
-    gate_assets: Res<GateAssets>,
-    plate_assets: Res<PlateAssets>,
-    audio: Res<Audio>,
+    mut plate_query: Query<&mut Plate>,
 ) {
     for &event in collision_events.read() {
-        let CollisionEvent::Started(entity1, entity2, _) = event else {
-            continue;
+        let (entity1, entity2, started) = match event {
+            CollisionEvent::Started(entity1, entity2, _) => (entity1, entity2, true),
+            CollisionEvent::Stopped(entity1, entity2, _) => (entity1, entity2, false),
         };
 
-        let mut handle_collision = |entity: Entity| {
-            let Ok((mut plate, mut plate_image)) = plate_query.get_mut(entity) else {
+        let mut handle_collision = |entity: Entity, occupant: Entity| {
+            let Ok(mut plate) = plate_query.get_mut(entity) else {
                 return;
             };
-            if plate.pressed {
-                return;
+
+            if !started {
+                plate.occupants.retain(|&x| x != occupant);
+            } else if !plate.occupants.contains(&occupant) {
+                plate.occupants.push(occupant);
             }
-            plate.pressed = true;
-            *plate_image = plate_assets.pressed.clone();
+        };
+
+        handle_collision(entity1, entity2);
+        handle_collision(entity2, entity1);
+    }
+}
+
+/// Press or release plates based on the total mass resting on them
+fn press_plates(
+    mut plate_query: Query<(&mut Plate, &mut Signal, &mut Handle<Image>)>,
+    mass_query: Query<&ColliderMassProperties>,
+    plate_assets: Res<PlateAssets>,
+    audio: Res<Audio>,
+) {
+    for (mut plate, mut signal, mut plate_image) in &mut plate_query {
+        // Forget occupants that were despawned while on the plate
+        plate.occupants.retain(|&x| mass_query.contains(x));
+
+        // Mass can change while on the plate (e.g. an actor dying and becoming a corpse)
+        let mass = plate
+            .occupants
+            .iter()
+            .filter_map(|&x| match mass_query.get(x) {
+                Ok(&ColliderMassProperties::Mass(mass)) => Some(mass),
+                _ => None,
+            })
+            .sum::<f32>();
+
+        let pressed = mass >= plate.threshold || (plate.latched && signal.0);
+        if pressed == signal.0 {
+            continue;
+        }
+        signal.0 = pressed;
 
+        if pressed {
+            *plate_image = plate_assets.pressed.clone();
             audio
                 .play(plate_assets.trigger_gate.clone())
                 .with_volume(0.8);
-
-            for &entity in &plate.gates {
-                let Ok((mut gate, mut gate_image, mut gate_groups)) = gate_query.get_mut(entity)
-                else {
-                    continue;
-                };
-
-                gate.open = !gate.open;
-                (gate_groups.filters, *gate_image) = if gate.open {
-                    (Group::empty(), gate_assets.open.clone())
-                } else {
-                    (COLLISION_GROUP, gate_assets.closed.clone())
-                };
-            }
-        };
-
-        handle_collision(entity1);
-        handle_collision(entity2);
+        } else {
+            *plate_image = plate_assets.unpressed.clone();
+        }
     }
 }
 
 pub struct PlateTemplate {
     pub transform: Transform,
-    pub gates: Vec<Entity>,
+    pub latched: bool,
+    pub threshold: f32,
 }
 
 impl PlateTemplate {
+    /// Heavy enough for Sai or a corpse, but not a living enemy
+    pub const DEFAULT_THRESHOLD: f32 = 5.0;
+
     pub fn spawn(self, commands: &mut Commands, plate_assets: &PlateAssets) -> Entity {
         commands
             .spawn((
@@ -104,15 +139,28 @@ impl PlateTemplate {
                 Collider::ball(2.0),
                 CollisionGroups {
                     memberships: COLLISION_GROUP,
-                    filters: PLAYER_HURTBOX_GROUP,
+                    filters: COLLISION_GROUP,
                 },
                 Sensor,
                 ActiveEvents::COLLISION_EVENTS,
+                Signal(false),
                 Plate {
-                    gates: self.gates,
+                    latched: self.latched,
+                    threshold: self.threshold,
                     ..default()
                 },
             ))
             .id()
     }
 }
+
+impl FromLevelEntity for PlateTemplate {
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError> {
+        Ok(Self {
+            transform: entity.transform,
+            // Plates have always stayed pressed, which hand-made levels rely on
+            latched: entity.field_or("latched", true)?,
+            threshold: entity.field_or("threshold", Self::DEFAULT_THRESHOLD)?,
+        })
+    }
+}
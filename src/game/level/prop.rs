@@ -0,0 +1,212 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::thread_rng;
+use rand::Rng;
+use strum::EnumIter;
+use strum::IntoEnumIterator;
+
+use crate::common::UpdateSet;
+use crate::game::actor::health::Health;
+use crate::game::combat::DeathEvent;
+use crate::game::combat::Faction;
+use crate::game::combat::COLLISION_GROUP;
+use crate::game::combat::HITBOX_GROUP;
+use crate::game::level::field::FieldError;
+use crate::game::level::pickup::Pickup;
+use crate::game::level::pickup::PickupTemplate;
+use crate::game::level::registry::register_entity_spawner;
+use crate::game::level::registry::LevelEntity;
+use crate::util::vfx::DropShadowTemplate;
+use crate::util::vfx::PickupFlashTemplate;
+use crate::util::vfx::VfxAssets;
+use crate::util::y_sort::YSort;
+use crate::util::DespawnSet;
+
+pub struct PropPlugin;
+
+impl Plugin for PropPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Prop>().register_type::<Breakable>();
+        for prop in Prop::iter() {
+            register_entity_spawner(app, prop.identifier(), move |commands, world, spawn| {
+                Ok(PropTemplate::from_level_entity(spawn, prop)?
+                    .spawn(commands, world.resource::<VfxAssets>()))
+            });
+        }
+
+        app.add_systems(Update, break_props.in_set(UpdateSet::HandleEvents));
+    }
+}
+
+#[derive(Component, Reflect, Copy, Clone, PartialEq, Eq, Debug, EnumIter)]
+pub enum Prop {
+    Crate,
+    Barrel,
+}
+
+impl Prop {
+    pub fn from_identifier(identifier: &str) -> Option<Self> {
+        Self::iter().find(|x| x.identifier() == identifier)
+    }
+
+    pub fn identifier(self) -> &'static str {
+        match self {
+            Prop::Crate => "crate",
+            Prop::Barrel => "barrel",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Prop::Crate => Color::rgb(0.6, 0.42, 0.25),
+            Prop::Barrel => Color::rgb(0.45, 0.3, 0.2),
+        }
+    }
+
+    fn collider(self) -> Collider {
+        match self {
+            Prop::Crate => Collider::cuboid(6.0, 6.0),
+            Prop::Barrel => Collider::ball(6.0),
+        }
+    }
+
+    fn default_mass(self) -> f32 {
+        match self {
+            Prop::Crate => 20.0,
+            Prop::Barrel => 12.0,
+        }
+    }
+}
+
+/// Breaks into its drops once its `Health` runs out
+#[derive(Component, Reflect, Default)]
+pub struct Breakable {
+    pub drops: Vec<Pickup>,
+}
+
+fn break_props(
+    mut commands: Commands,
+    mut death_events: EventReader<DeathEvent>,
+    mut despawn: ResMut<DespawnSet>,
+    prop_query: Query<(&Prop, &Breakable, &GlobalTransform, Option<&Parent>)>,
+) {
+    let mut rng = thread_rng();
+
+    for &DeathEvent { entity, .. } in death_events.read() {
+        let Ok((&prop, breakable, gt, parent)) = prop_query.get(entity) else {
+            continue;
+        };
+        let pos = gt.translation();
+
+        PickupFlashTemplate {
+            transform: Transform::from_translation(pos),
+            color: prop.color(),
+        }
+        .spawn(&mut commands);
+
+        for &pickup in &breakable.drops {
+            let scatter = Vec2::new(rng.gen_range(-4.0..4.0), rng.gen_range(-4.0..4.0));
+            let drop = PickupTemplate {
+                transform: Transform::from_translation(pos + scatter.extend(0.0)),
+                pickup,
+            }
+            .spawn(&mut commands);
+            if let Some(parent) = parent {
+                commands.entity(parent.get()).add_child(drop);
+            }
+        }
+
+        despawn.recursive(entity);
+    }
+}
+
+pub struct PropTemplate {
+    pub transform: Transform,
+    pub prop: Prop,
+    pub mass: Option<f32>,
+    pub friction: f32,
+    pub damping: f32,
+    /// Unbreakable if `None`
+    pub health: Option<f32>,
+    pub drops: Vec<Pickup>,
+}
+
+impl PropTemplate {
+    pub fn new(transform: Transform, prop: Prop) -> Self {
+        Self {
+            transform,
+            prop,
+            mass: None,
+            friction: 0.0,
+            damping: 8.0,
+            health: None,
+            drops: vec![],
+        }
+    }
+
+    pub fn spawn(self, commands: &mut Commands, vfx_assets: &VfxAssets) -> Entity {
+        // Children
+        let drop_shadow = DropShadowTemplate::default().spawn(commands, vfx_assets);
+
+        // Parent
+        let mut collision_groups = CollisionGroups {
+            memberships: COLLISION_GROUP,
+            filters: COLLISION_GROUP,
+        };
+        if self.health.is_some() {
+            // Anyone can hit a breakable prop, like they can hit wildlife
+            collision_groups.memberships |= Faction::Wildlife.hurtbox_groups().memberships;
+            collision_groups.filters |= HITBOX_GROUP;
+        }
+
+        let mut entity = commands.spawn((
+            Name::new(format!("{:?}", self.prop)),
+            SpriteBundle {
+                sprite: Sprite {
+                    color: self.prop.color(),
+                    custom_size: Some(Vec2::splat(12.0)),
+                    ..default()
+                },
+                transform: self.transform,
+                ..default()
+            },
+            RigidBody::Dynamic,
+            LockedAxes::ROTATION_LOCKED,
+            Velocity::default(),
+            self.prop.collider(),
+            ColliderMassProperties::Mass(self.mass.unwrap_or(self.prop.default_mass())),
+            Friction::new(self.friction),
+            Damping {
+                linear_damping: self.damping,
+                angular_damping: 0.0,
+            },
+            collision_groups,
+            SolverGroups {
+                memberships: COLLISION_GROUP,
+                filters: COLLISION_GROUP,
+            },
+            YSort,
+            self.prop,
+        ));
+        if let Some(health) = self.health {
+            entity.insert((Health::full(health), Breakable { drops: self.drops }));
+        }
+
+        entity.add_child(drop_shadow).id()
+    }
+}
+
+impl PropTemplate {
+    /// Like `FromLevelEntity`, but the kind of prop comes from the identifier
+    fn from_level_entity(entity: &LevelEntity, prop: Prop) -> Result<Self, FieldError> {
+        let base = Self::new(entity.transform, prop);
+        Ok(Self {
+            mass: entity.field("mass")?.or(base.mass),
+            friction: entity.field_or("friction", base.friction)?,
+            damping: entity.field_or("damping", base.damping)?,
+            health: entity.field::<f32>("health")?.filter(|&x| x > 0.0),
+            drops: entity.field_or("drops", vec![])?,
+            ..base
+        })
+    }
+}
@@ -0,0 +1,257 @@
+use bevy::ecs::system::CommandQueue;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy::utils::HashSet;
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::game::level::field::read_field;
+use crate::game::level::field::FieldError;
+use crate::game::level::field::FromField;
+use crate::util::grid::merge_cells;
+use crate::util::DespawnSet;
+
+pub struct RegistryPlugin;
+
+impl Plugin for RegistryPlugin {
+    fn build(&self, app: &mut App) {
+        app.world
+            .get_resource_or_insert_with(SpawnerRegistry::default);
+
+        app.add_systems(PreUpdate, populate_level);
+    }
+}
+
+/// Used for tiles outside of a tile layer
+const DEFAULT_GRID_SIZE: f32 = 16.0;
+
+type EntitySpawner =
+    Box<dyn Fn(&mut Commands, &World, &LevelEntity) -> Result<Entity, FieldError> + Send + Sync>;
+type EntityAlias =
+    Box<dyn Fn(&World, &EntityInstance) -> Result<Option<String>, FieldError> + Send + Sync>;
+type TileSpawner = Box<dyn Fn(&mut Commands, &World, Transform, Vec2) -> Entity + Send + Sync>;
+type RefResolver = Box<dyn Fn(&mut World, Entity, &[Entity]) + Send + Sync>;
+
+/// How to spawn each kind of LDtk entity and tile, and how to link entity reference fields
+#[derive(Resource, Default)]
+pub struct SpawnerRegistry {
+    entities: HashMap<&'static str, EntitySpawner>,
+    aliases: HashMap<&'static str, EntityAlias>,
+    tiles: HashMap<&'static str, TileSpawner>,
+    refs: HashMap<&'static str, RefResolver>,
+}
+
+fn registry(app: &mut App) -> Mut<SpawnerRegistry> {
+    app.world
+        .get_resource_or_insert_with(SpawnerRegistry::default)
+}
+
+/// Spawn LDtk entity instances with the given identifier
+pub fn register_entity_spawner(
+    app: &mut App,
+    identifier: &'static str,
+    spawner: impl Fn(&mut Commands, &World, &LevelEntity) -> Result<Entity, FieldError>
+        + Send
+        + Sync
+        + 'static,
+) {
+    registry(app).entities.insert(identifier, Box::new(spawner));
+}
+
+/// Decide which spawner to use for LDtk entity instances with the given identifier when the
+/// level spawns, or skip the instance by returning `None`
+pub fn register_entity_alias(
+    app: &mut App,
+    identifier: &'static str,
+    alias: impl Fn(&World, &EntityInstance) -> Result<Option<String>, FieldError>
+        + Send
+        + Sync
+        + 'static,
+) {
+    registry(app).aliases.insert(identifier, Box::new(alias));
+}
+
+/// Spawn an entity for each rectangle of contiguous tiles with the given enum tag, given its
+/// center and size
+pub fn register_tile_spawner(
+    app: &mut App,
+    tag: &'static str,
+    spawner: impl Fn(&mut Commands, &World, Transform, Vec2) -> Entity + Send + Sync + 'static,
+) {
+    registry(app).tiles.insert(tag, Box::new(spawner));
+}
+
+/// Link the entities referenced by the given `EntityRef` or `EntityRefs` field, once every
+/// entity in the level has been spawned
+pub fn register_ref_resolver(
+    app: &mut App,
+    field: &'static str,
+    resolver: impl Fn(&mut World, Entity, &[Entity]) + Send + Sync + 'static,
+) {
+    registry(app).refs.insert(field, Box::new(resolver));
+}
+
+/// An LDtk entity instance being spawned
+pub struct LevelEntity<'a> {
+    /// The identifier being spawned, which differs from the instance's if it was aliased
+    pub identifier: &'a str,
+    pub transform: Transform,
+    pub instance: &'a EntityInstance,
+    placements: &'a HashMap<String, Transform>,
+}
+
+impl LevelEntity<'_> {
+    /// The transform of another entity instance in the same level
+    pub fn placement(&self, iid: &str) -> Option<Transform> {
+        self.placements.get(iid).copied()
+    }
+
+    /// Read a typed field, treating a missing field or null value as `None`
+    pub fn field<T: FromField>(&self, field: &str) -> Result<Option<T>, FieldError> {
+        read_field(self.instance, field)
+    }
+
+    pub fn field_or<T: FromField>(&self, field: &str, default: T) -> Result<T, FieldError> {
+        Ok(self.field(field)?.unwrap_or(default))
+    }
+}
+
+/// Build a template from an LDtk entity instance's placement and fields
+pub trait FromLevelEntity: Sized {
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError>;
+}
+
+fn populate_level(
+    world: &mut World,
+    entity_query: &mut QueryState<
+        (Entity, &Parent, &Transform, &EntityInstance),
+        Added<EntityInstance>,
+    >,
+    tile_query: &mut QueryState<(&Parent, &Transform, &TileEnumTags), Added<TileEnumTags>>,
+) {
+    let markers = entity_query
+        .iter(world)
+        .map(|(entity, parent, &transform, instance)| {
+            (entity, parent.get(), transform, instance.clone())
+        })
+        .collect::<Vec<_>>();
+    let tiles = tile_query
+        .iter(world)
+        .map(|(parent, &transform, tile)| (parent.get(), transform, tile.tags.clone()))
+        .collect::<Vec<_>>();
+    if markers.is_empty() && tiles.is_empty() {
+        return;
+    }
+
+    // Despawn marker entities
+    let mut despawn = world.resource_mut::<DespawnSet>();
+    for &(marker, ..) in &markers {
+        despawn.recursive(marker);
+    }
+
+    let placements = markers
+        .iter()
+        .map(|(_, _, transform, instance)| (instance.iid.clone(), *transform))
+        .collect::<HashMap<_, _>>();
+
+    world.resource_scope(|world, registry: Mut<SpawnerRegistry>| {
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, world);
+
+        let mut iid_map = HashMap::default();
+        for (_, parent, transform, instance) in &markers {
+            let identifier = match registry.aliases.get(instance.identifier.as_str()) {
+                Some(alias) => match alias(world, instance) {
+                    Ok(Some(identifier)) => identifier,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        error!("{e}");
+                        continue;
+                    },
+                },
+                None => instance.identifier.clone(),
+            };
+            let Some(spawner) = registry.entities.get(identifier.as_str()) else {
+                warn!(
+                    "No spawner for LDtk entity \"{identifier}\" ({})",
+                    instance.iid,
+                );
+                continue;
+            };
+
+            let spawn = LevelEntity {
+                identifier: &identifier,
+                transform: *transform,
+                instance,
+                placements: &placements,
+            };
+            let entity = match spawner(&mut commands, world, &spawn) {
+                Ok(entity) => entity,
+                Err(e) => {
+                    error!("{e}");
+                    continue;
+                },
+            };
+            commands.entity(*parent).add_child(entity);
+            iid_map.insert(instance.iid.as_str(), entity);
+        }
+
+        // Merge each layer's tiles per tag, so colliders don't snag on the seams between tiles
+        let mut layers = HashMap::<_, (f32, f32, HashSet<IVec2>)>::default();
+        for (parent, transform, tags) in &tiles {
+            let grid_size = world
+                .get::<LayerMetadata>(*parent)
+                .map_or(DEFAULT_GRID_SIZE, |layer| layer.grid_size as f32);
+            let cell = (transform.translation.truncate() / grid_size)
+                .floor()
+                .as_ivec2();
+            for tag in tags {
+                let Some((tag, _)) = registry.tiles.get_key_value(tag.as_str()) else {
+                    continue;
+                };
+                layers
+                    .entry((*parent, *tag))
+                    .or_insert((grid_size, transform.translation.z, HashSet::default()))
+                    .2
+                    .insert(cell);
+            }
+        }
+
+        let mut layers = layers.into_iter().collect::<Vec<_>>();
+        layers.sort_by_key(|&((parent, tag), _)| (parent, tag));
+        for ((parent, tag), (grid_size, z, cells)) in layers {
+            for rect in merge_cells(&cells) {
+                let size = rect.size().as_vec2() * grid_size;
+                let center = rect.min.as_vec2() * grid_size + size / 2.0;
+                let transform = Transform::from_translation(center.extend(z));
+
+                let entity = registry.tiles[tag](&mut commands, world, transform, size);
+                commands.entity(parent).add_child(entity);
+            }
+        }
+
+        queue.apply(world);
+
+        // Resolve entity references now that every entity exists
+        for (_, _, _, instance) in &markers {
+            let Some(&entity) = iid_map.get(instance.iid.as_str()) else {
+                continue;
+            };
+
+            for field in &instance.field_instances {
+                let Some(resolver) = registry.refs.get(field.identifier.as_str()) else {
+                    continue;
+                };
+                let targets = match &field.value {
+                    FieldValue::EntityRef(entity_ref) => entity_ref.iter().collect::<Vec<_>>(),
+                    FieldValue::EntityRefs(entity_refs) => entity_refs.iter().flatten().collect(),
+                    _ => continue,
+                }
+                .into_iter()
+                .filter_map(|entity_ref| iid_map.get(entity_ref.entity_iid.as_str()).copied())
+                .collect::<Vec<_>>();
+
+                resolver(world, entity, &targets);
+            }
+        }
+    });
+}
@@ -0,0 +1,411 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_ecs_ldtk::prelude::FieldValue;
+use bevy_rapier2d::prelude::*;
+
+use crate::common::UpdateSet;
+use crate::game::actor::intent::ActorIntent;
+use crate::game::combat::COLLISION_GROUP;
+use crate::game::combat::PLAYER_HURTBOX_GROUP;
+use crate::game::level::field::enum_value;
+use crate::game::level::field::FieldError;
+use crate::game::level::field::FromField;
+use crate::game::level::registry::register_entity_spawner;
+use crate::game::level::registry::register_ref_resolver;
+use crate::game::level::registry::FromLevelEntity;
+use crate::game::level::registry::LevelEntity;
+use crate::util::y_sort::YSort;
+
+pub struct SignalPlugin;
+
+impl Plugin for SignalPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Signal>()
+            .register_type::<SignalInputs>()
+            .register_type::<SignalLogic>()
+            .add_systems(Update, propagate_signals.in_set(UpdateSet::Update));
+        register_entity_spawner(app, "logic", |commands, _, spawn| {
+            Ok(SignalLogicTemplate::from_level_entity(spawn)?.spawn(commands))
+        });
+
+        // An entity's `inputs` feed into it, and its `targets` are fed by it
+        register_ref_resolver(app, "inputs", |world, entity, inputs| {
+            for &input in inputs {
+                link_signal(world, input, entity);
+            }
+        });
+        for field in ["targets", "gates"] {
+            register_ref_resolver(app, field, |world, entity, targets| {
+                for &target in targets {
+                    link_signal(world, entity, target);
+                }
+            });
+        }
+
+        app.register_type::<Lever>()
+            .add_systems(Update, update_lever_sprites.in_set(UpdateSet::UpdateUi));
+        register_entity_spawner(app, "lever", |commands, _, spawn| {
+            Ok(LeverTemplate::from_level_entity(spawn)?.spawn(commands))
+        });
+
+        app.register_type::<SignalKey>()
+            .add_systems(Update, collect_keys.in_set(UpdateSet::Start));
+        register_entity_spawner(app, "key", |commands, _, spawn| {
+            Ok(SignalKeyTemplate {
+                transform: spawn.transform,
+            }
+            .spawn(commands))
+        });
+
+        app.register_type::<SignalTimer>()
+            .add_systems(Update, update_signal_timers.in_set(UpdateSet::Start));
+        register_entity_spawner(app, "timer", |commands, _, spawn| {
+            Ok(SignalTimerTemplate::from_level_entity(spawn)?.spawn(commands))
+        });
+
+        app.register_type::<DeathSignal>()
+            .add_systems(Update, update_death_signals.in_set(UpdateSet::Start));
+    }
+}
+
+/// The output of a signal source, logic node, or target
+#[derive(Component, Reflect, Default)]
+pub struct Signal(pub bool);
+
+/// The entities whose signals feed into this one's `SignalLogic`
+#[derive(Component, Reflect, Default)]
+pub struct SignalInputs(pub Vec<Entity>);
+
+#[derive(Reflect, Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum LogicOp {
+    /// Active if any input is active
+    #[default]
+    Or,
+    /// Active if every input is active
+    And,
+    /// Active if no input is active
+    Not,
+    /// Active if an odd number of inputs are active, so each input switching flips it
+    Toggle,
+}
+
+impl LogicOp {
+    pub fn from_identifier(identifier: &str) -> Option<Self> {
+        Some(match identifier {
+            "Or" | "or" => LogicOp::Or,
+            "And" | "and" => LogicOp::And,
+            "Not" | "not" => LogicOp::Not,
+            "Toggle" | "toggle" | "Xor" | "xor" => LogicOp::Toggle,
+            _ => return None,
+        })
+    }
+
+    fn eval(self, mut inputs: impl Iterator<Item = bool>) -> bool {
+        match self {
+            LogicOp::Or => inputs.any(|x| x),
+            LogicOp::And => inputs.all(|x| x),
+            LogicOp::Not => !inputs.any(|x| x),
+            LogicOp::Toggle => inputs.filter(|&x| x).count() % 2 == 1,
+        }
+    }
+}
+
+impl FromField for LogicOp {
+    const EXPECTED: &'static str = "an Or, And, Not or Toggle enum";
+
+    fn from_field(value: &FieldValue) -> Option<Self> {
+        Self::from_identifier(enum_value(value)?)
+    }
+}
+
+/// Combines `SignalInputs` into this entity's `Signal`, optionally after a delay
+#[derive(Component, Reflect, Default)]
+pub struct SignalLogic {
+    pub op: LogicOp,
+    pub delay: f32,
+    pending: Option<(bool, f32)>,
+}
+
+impl SignalLogic {
+    pub fn new(op: LogicOp, delay: f32) -> Self {
+        Self {
+            op,
+            delay,
+            pending: None,
+        }
+    }
+}
+
+/// Feed `from`'s signal into `to`
+fn link_signal(world: &mut World, from: Entity, to: Entity) {
+    if let Some(mut inputs) = world.get_mut::<SignalInputs>(to) {
+        inputs.0.push(from);
+    } else if let Some(mut entity) = world.get_entity_mut(to) {
+        entity.insert(SignalInputs(vec![from]));
+    }
+}
+
+pub fn propagate_signals(
+    mut logic_query: Query<(Entity, &mut SignalLogic, Option<&SignalInputs>)>,
+    mut signal_query: Query<&mut Signal>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+
+    // Read every input up front so evaluation order doesn't matter
+    let mut snapshot = HashMap::default();
+    for (_, _, inputs) in &logic_query {
+        for &input in inputs.into_iter().flat_map(|inputs| &inputs.0) {
+            if let Ok(signal) = signal_query.get(input) {
+                snapshot.insert(input, signal.0);
+            }
+        }
+    }
+
+    for (entity, mut logic, inputs) in &mut logic_query {
+        let Ok(mut signal) = signal_query.get_mut(entity) else {
+            continue;
+        };
+
+        let value = logic.op.eval(
+            inputs
+                .into_iter()
+                .flat_map(|inputs| &inputs.0)
+                .filter_map(|input| snapshot.get(input).copied()),
+        );
+        if value == signal.0 {
+            logic.pending = None;
+            continue;
+        }
+
+        // Wait out the delay before switching
+        let remaining = match logic.pending {
+            Some((pending, remaining)) if pending == value => remaining - dt,
+            _ => logic.delay,
+        };
+        if remaining <= 0.0 {
+            signal.0 = value;
+            logic.pending = None;
+        } else {
+            logic.pending = Some((value, remaining));
+        }
+    }
+}
+
+/// A switch the player can flip by interacting with it
+#[derive(Component, Reflect)]
+pub struct Lever;
+
+impl Lever {
+    const ON_COLOR: Color = Color::rgb(0.9, 0.75, 0.3);
+    const OFF_COLOR: Color = Color::rgb(0.45, 0.4, 0.35);
+}
+
+fn update_lever_sprites(
+    mut lever_query: Query<(&Signal, &mut Sprite), (With<Lever>, Changed<Signal>)>,
+) {
+    for (signal, mut sprite) in &mut lever_query {
+        sprite.color = if signal.0 {
+            Lever::ON_COLOR
+        } else {
+            Lever::OFF_COLOR
+        };
+    }
+}
+
+pub struct LeverTemplate {
+    pub transform: Transform,
+    pub on: bool,
+}
+
+impl LeverTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("Lever"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: if self.on {
+                            Lever::ON_COLOR
+                        } else {
+                            Lever::OFF_COLOR
+                        },
+                        custom_size: Some(Vec2::new(3.0, 7.0)),
+                        ..default()
+                    },
+                    transform: self.transform,
+                    ..default()
+                },
+                YSort,
+                Signal(self.on),
+                Lever,
+            ))
+            .id()
+    }
+}
+
+impl FromLevelEntity for LeverTemplate {
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError> {
+        Ok(Self {
+            transform: entity.transform,
+            on: entity.field_or("on", false)?,
+        })
+    }
+}
+
+/// A key that activates its signal once the player picks it up
+#[derive(Component, Reflect)]
+pub struct SignalKey;
+
+fn collect_keys(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut key_query: Query<(&mut Signal, &mut Visibility), With<SignalKey>>,
+) {
+    for &event in collision_events.read() {
+        let CollisionEvent::Started(entity1, entity2, _) = event else {
+            continue;
+        };
+
+        for entity in [entity1, entity2] {
+            let Ok((mut signal, mut visibility)) = key_query.get_mut(entity) else {
+                continue;
+            };
+
+            // Keep the entity around so its signal stays on
+            signal.0 = true;
+            *visibility = Visibility::Hidden;
+            commands.entity(entity).remove::<Collider>();
+        }
+    }
+}
+
+pub struct SignalKeyTemplate {
+    pub transform: Transform,
+}
+
+impl SignalKeyTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("Key"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgb(0.95, 0.8, 0.2),
+                        custom_size: Some(Vec2::new(5.0, 3.0)),
+                        ..default()
+                    },
+                    transform: self.transform,
+                    ..default()
+                },
+                Collider::ball(4.0),
+                CollisionGroups {
+                    memberships: COLLISION_GROUP,
+                    filters: PLAYER_HURTBOX_GROUP,
+                },
+                Sensor,
+                ActiveEvents::COLLISION_EVENTS,
+                YSort,
+                Signal(false),
+                SignalKey,
+            ))
+            .id()
+    }
+}
+
+/// Switches its signal on for `on_time` out of every `period` seconds
+#[derive(Component, Reflect)]
+pub struct SignalTimer {
+    pub period: f32,
+    pub on_time: f32,
+    pub t: f32,
+}
+
+fn update_signal_timers(mut timer_query: Query<(&mut SignalTimer, &mut Signal)>, time: Res<Time>) {
+    let dt = time.delta_seconds();
+
+    for (mut timer, mut signal) in &mut timer_query {
+        timer.t = (timer.t + dt) % timer.period.max(f32::EPSILON);
+        let active = timer.t < timer.on_time;
+        if signal.0 != active {
+            signal.0 = active;
+        }
+    }
+}
+
+pub struct SignalTimerTemplate {
+    pub transform: Transform,
+    pub period: f32,
+    pub on_time: f32,
+}
+
+impl SignalTimerTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("SignalTimer"),
+                TransformBundle::from_transform(self.transform),
+                SignalTimer {
+                    period: self.period,
+                    on_time: self.on_time,
+                    t: 0.0,
+                },
+                Signal(false),
+            ))
+            .id()
+    }
+}
+
+impl FromLevelEntity for SignalTimerTemplate {
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError> {
+        Ok(Self {
+            transform: entity.transform,
+            period: entity.field_or("period", 2.0)?,
+            on_time: entity.field_or("on_time", 1.0)?,
+        })
+    }
+}
+
+/// Marks an actor whose signal turns on when it dies
+#[derive(Component, Reflect)]
+pub struct DeathSignal;
+
+fn update_death_signals(
+    mut signal_query: Query<&mut Signal, (With<DeathSignal>, Without<ActorIntent>)>,
+) {
+    for mut signal in &mut signal_query {
+        if !signal.0 {
+            signal.0 = true;
+        }
+    }
+}
+
+/// A logic node with no presence in the world, for combining and delaying signals
+pub struct SignalLogicTemplate {
+    pub transform: Transform,
+    pub op: LogicOp,
+    pub delay: f32,
+}
+
+impl SignalLogicTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("SignalLogic"),
+                TransformBundle::from_transform(self.transform),
+                SignalLogic::new(self.op, self.delay),
+                Signal(false),
+            ))
+            .id()
+    }
+}
+
+impl FromLevelEntity for SignalLogicTemplate {
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError> {
+        Ok(Self {
+            transform: entity.transform,
+            op: entity.field_or("op", LogicOp::default())?,
+            delay: entity.field_or("delay", 0.0)?,
+        })
+    }
+}
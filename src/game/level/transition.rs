@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::common::UpdateSet;
+use crate::sequence::screen_fade;
+use crate::sequence::SequenceState;
+use crate::util::animation::transition::fade_in;
+use crate::util::animation::transition::fade_out_and_hold;
+use crate::util::animation::tween::TweenFinished;
+
+pub struct LevelTransitionPlugin;
+
+impl Plugin for LevelTransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<LevelTransition>()
+            .init_resource::<LevelTransition>()
+            .add_systems(Update, update_level_transition.in_set(UpdateSet::Start))
+            .add_systems(OnExit(SequenceState::Game), reset_level_transition);
+    }
+}
+
+const FADE_OUT_SECS: f32 = 0.4;
+const FADE_IN_SECS: f32 = 0.4;
+
+#[derive(Reflect, Default, Clone, Debug)]
+enum TransitionPhase {
+    #[default]
+    Idle,
+    /// Waiting to start fading out to the target level
+    Requested(String),
+    /// Waiting for the screen to go dark
+    FadingOut(String, Entity),
+    /// Waiting for the target level to finish spawning behind the dark screen
+    Loading(String, Entity),
+}
+
+/// Fades between levels, freezing gameplay until the next level has spawned
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct LevelTransition {
+    phase: TransitionPhase,
+}
+
+impl LevelTransition {
+    /// Start a transition to the level with the given iid, unless one is already running
+    pub fn start(&mut self, level_iid: String) {
+        if self.is_active() {
+            return;
+        }
+        self.phase = TransitionPhase::Requested(level_iid);
+    }
+
+    pub fn is_active(&self) -> bool {
+        !matches!(self.phase, TransitionPhase::Idle)
+    }
+}
+
+fn update_level_transition(
+    mut commands: Commands,
+    mut transition: ResMut<LevelTransition>,
+    mut fade_events: EventReader<TweenFinished>,
+    mut level_events: EventReader<LevelEvent>,
+    mut level_selection: ResMut<LevelSelection>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    level_query: Query<(Entity, &LevelIid)>,
+) {
+    // Drain events every frame so stale ones can't complete a later transition
+    let finished_fades = fade_events
+        .read()
+        .map(|event| event.entity)
+        .collect::<Vec<_>>();
+    let transformed_levels = level_events
+        .read()
+        .filter_map(|event| match event {
+            LevelEvent::Transformed(iid) => Some(iid.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let phase = transition.phase.clone();
+    match phase {
+        TransitionPhase::Idle => {},
+        TransitionPhase::Requested(target) => {
+            // Freeze gameplay for the duration of the transition
+            virtual_time.pause();
+            rapier_config.physics_pipeline_active = false;
+
+            // The same overlay fades back in, so the level swap is never visible
+            let fade = screen_fade(&mut commands, "LevelFade", fade_out_and_hold(FADE_OUT_SECS));
+            transition.phase = TransitionPhase::FadingOut(target, fade);
+        },
+        TransitionPhase::FadingOut(target, fade) => {
+            if !finished_fades.contains(&fade) {
+                return;
+            }
+
+            // LDtk won't respawn a level that's already spawned, so ask for it explicitly
+            let target_iid = LevelIid::new(target.clone());
+            for (entity, iid) in &level_query {
+                if *iid == target_iid {
+                    commands.entity(entity).insert(Respawn);
+                }
+            }
+            *level_selection = LevelSelection::Iid(target_iid);
+            transition.phase = TransitionPhase::Loading(target, fade);
+        },
+        TransitionPhase::Loading(target, fade) => {
+            if !transformed_levels.iter().any(|iid| iid.as_str() == target) {
+                return;
+            }
+
+            virtual_time.unpause();
+            rapier_config.physics_pipeline_active = true;
+            if let Some(mut fade) = commands.get_entity(fade) {
+                fade.insert(fade_in(FADE_IN_SECS));
+            }
+            transition.phase = TransitionPhase::Idle;
+        },
+    }
+}
+
+/// Unfreeze gameplay and remove the overlay in case the game ends mid-transition
+fn reset_level_transition(
+    mut commands: Commands,
+    mut transition: ResMut<LevelTransition>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    if let TransitionPhase::FadingOut(_, fade) | TransitionPhase::Loading(_, fade) =
+        transition.phase
+    {
+        if let Some(fade) = commands.get_entity(fade) {
+            fade.despawn_recursive();
+        }
+    }
+    *transition = default();
+    virtual_time.unpause();
+    rapier_config.physics_pipeline_active = true;
+}
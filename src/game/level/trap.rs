@@ -0,0 +1,463 @@
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::FieldValue;
+use bevy_rapier2d::prelude::*;
+
+use crate::common::UpdateSet;
+use crate::game::actor::health::Health;
+use crate::game::actor::intent::ActorIntent;
+use crate::game::actor::Actor;
+use crate::game::combat::projectile::Projectile;
+use crate::game::combat::projectile::ProjectileTemplate;
+use crate::game::combat::weapon::HitboxShape;
+use crate::game::combat::DamageEvents;
+use crate::game::combat::DamageType;
+use crate::game::combat::Faction;
+use crate::game::combat::HitboxTemplate;
+use crate::game::combat::Resistances;
+use crate::game::combat::COLLISION_GROUP;
+use crate::game::level::field::enum_value;
+use crate::game::level::field::FieldError;
+use crate::game::level::field::FromField;
+use crate::game::level::prop::Prop;
+use crate::game::level::registry::register_entity_spawner;
+use crate::game::level::registry::FromLevelEntity;
+use crate::game::level::registry::LevelEntity;
+use crate::game::level::signal::propagate_signals;
+use crate::game::level::signal::LogicOp;
+use crate::game::level::signal::Signal;
+use crate::game::level::signal::SignalLogic;
+use crate::game::level::signal::SignalTimer;
+use crate::util::DespawnSet;
+
+pub struct TrapPlugin;
+
+impl Plugin for TrapPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SpikeTrap>()
+            .register_type::<ArrowLauncher>()
+            .add_systems(
+                Update,
+                (strike_spikes, fire_arrow_launchers)
+                    .in_set(UpdateSet::Update)
+                    .after(propagate_signals),
+            );
+        register_entity_spawner(app, "spikes", |commands, _, spawn| {
+            Ok(SpikeTrapTemplate::from_level_entity(spawn)?.spawn(commands))
+        });
+        register_entity_spawner(app, "arrow_launcher", |commands, _, spawn| {
+            Ok(ArrowLauncherTemplate::from_level_entity(spawn)?.spawn(commands))
+        });
+
+        app.register_type::<Pit>()
+            .register_type::<SpawnPoint>()
+            .add_systems(
+                Update,
+                (
+                    record_spawn_points.in_set(UpdateSet::Start),
+                    drop_into_pits.in_set(UpdateSet::Start),
+                ),
+            );
+        register_entity_spawner(app, "pit", |commands, _, spawn| {
+            Ok(PitTemplate::from_level_entity(spawn)?.spawn(commands))
+        });
+    }
+}
+
+/// Traps hurt every faction, so they're attributed to nobody and ignore alliances
+const TRAP_FACTION: Faction = Faction::Wildlife;
+
+/// How a trap decides when it's active
+pub enum TrapTrigger {
+    /// Driven by linked signals
+    Signal { op: LogicOp, delay: f32 },
+    /// Active for `on_time` out of every `period` seconds
+    Timer { period: f32, on_time: f32 },
+}
+
+impl TrapTrigger {
+    /// Traps cycle on their own if given a `period`, otherwise they wait for linked signals
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError> {
+        Ok(match entity.field::<f32>("period")? {
+            Some(period) => TrapTrigger::Timer {
+                period,
+                on_time: entity.field_or("on_time", period / 2.0)?,
+            },
+            None => TrapTrigger::Signal {
+                op: entity.field_or("op", LogicOp::default())?,
+                delay: entity.field_or("delay", 0.0)?,
+            },
+        })
+    }
+
+    fn insert(self, entity: &mut EntityCommands) {
+        match self {
+            TrapTrigger::Signal { op, delay } => {
+                entity.insert(SignalLogic::new(op, delay));
+            },
+            TrapTrigger::Timer { period, on_time } => {
+                entity.insert(SignalTimer {
+                    period,
+                    on_time,
+                    t: 0.0,
+                });
+            },
+        }
+        entity.insert(Signal(false));
+    }
+}
+
+/// Spikes that stab anything standing on them while raised
+#[derive(Component, Reflect)]
+pub struct SpikeTrap {
+    pub damage: f32,
+    strike_t: f32,
+}
+
+impl SpikeTrap {
+    /// Time between strikes while the spikes stay raised
+    const STRIKE_INTERVAL: f32 = 0.6;
+    const RAISED_COLOR: Color = Color::rgb(0.75, 0.75, 0.8);
+    const LOWERED_COLOR: Color = Color::rgb(0.35, 0.35, 0.4);
+}
+
+fn strike_spikes(
+    mut commands: Commands,
+    mut spike_query: Query<(&mut SpikeTrap, &Signal, &GlobalTransform, &mut Sprite)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+
+    for (mut spikes, signal, gt, mut sprite) in &mut spike_query {
+        if !signal.0 {
+            sprite.color = SpikeTrap::LOWERED_COLOR;
+            spikes.strike_t = 0.0;
+            continue;
+        }
+        sprite.color = SpikeTrap::RAISED_COLOR;
+
+        spikes.strike_t -= dt;
+        if spikes.strike_t > 0.0 {
+            continue;
+        }
+        spikes.strike_t = SpikeTrap::STRIKE_INTERVAL;
+
+        HitboxTemplate {
+            position: gt.translation(),
+            direction: Vec2::ZERO,
+            shape: HitboxShape::Cuboid(Vec2::splat(6.0)),
+            attacker: None,
+            damage: spikes.damage,
+            damage_type: DamageType::Environmental,
+            knockback: 0.0,
+            status_effects: vec![],
+            faction: TRAP_FACTION,
+            friendly_fire: true,
+            success_sound: None,
+            failure_sound: None,
+        }
+        .spawn(&mut commands);
+    }
+}
+
+pub struct SpikeTrapTemplate {
+    pub transform: Transform,
+    pub damage: f32,
+    pub trigger: TrapTrigger,
+}
+
+impl SpikeTrapTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        let mut entity = commands.spawn((
+            Name::new("SpikeTrap"),
+            SpriteBundle {
+                sprite: Sprite {
+                    color: SpikeTrap::LOWERED_COLOR,
+                    custom_size: Some(Vec2::splat(12.0)),
+                    ..default()
+                },
+                transform: self.transform,
+                ..default()
+            },
+            SpikeTrap {
+                damage: self.damage,
+                strike_t: 0.0,
+            },
+        ));
+        self.trigger.insert(&mut entity);
+        entity.id()
+    }
+}
+
+impl FromLevelEntity for SpikeTrapTemplate {
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError> {
+        Ok(Self {
+            transform: entity.transform,
+            damage: entity.field_or("damage", 10.0)?,
+            trigger: TrapTrigger::from_level_entity(entity)?,
+        })
+    }
+}
+
+/// Fires an arrow each time its signal turns on
+#[derive(Component, Reflect)]
+pub struct ArrowLauncher {
+    pub direction: Vec2,
+    pub damage: f32,
+    was_active: bool,
+}
+
+fn fire_arrow_launchers(
+    mut commands: Commands,
+    mut launcher_query: Query<(&mut ArrowLauncher, &Signal, &GlobalTransform)>,
+) {
+    for (mut launcher, signal, gt) in &mut launcher_query {
+        let fire = signal.0 && !launcher.was_active;
+        launcher.was_active = signal.0;
+        if !fire {
+            continue;
+        }
+
+        ProjectileTemplate {
+            position: gt.translation() + 8.0 * launcher.direction.extend(0.0),
+            direction: launcher.direction,
+            projectile: Projectile::arrow(),
+            shape: HitboxShape::Ball(3.0),
+            attacker: None,
+            damage: launcher.damage,
+            damage_type: DamageType::Pierce,
+            knockback: 3.0,
+            status_effects: vec![],
+            faction: TRAP_FACTION,
+            friendly_fire: true,
+            success_sound: None,
+        }
+        .spawn(&mut commands);
+    }
+}
+
+pub struct ArrowLauncherTemplate {
+    pub transform: Transform,
+    pub direction: Vec2,
+    pub damage: f32,
+    pub trigger: TrapTrigger,
+}
+
+impl ArrowLauncherTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        let mut entity = commands.spawn((
+            Name::new("ArrowLauncher"),
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgb(0.4, 0.3, 0.25),
+                    custom_size: Some(Vec2::new(6.0, 6.0)),
+                    ..default()
+                },
+                transform: self.transform,
+                ..default()
+            },
+            ArrowLauncher {
+                direction: self.direction.normalize_or_zero(),
+                damage: self.damage,
+                was_active: false,
+            },
+        ));
+        self.trigger.insert(&mut entity);
+        entity.id()
+    }
+}
+
+impl FromLevelEntity for ArrowLauncherTemplate {
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError> {
+        Ok(Self {
+            transform: entity.transform,
+            direction: entity.field_or("direction", Direction::Right)?.vec(),
+            damage: entity.field_or("damage", 8.0)?,
+            trigger: TrapTrigger::from_level_entity(entity)?,
+        })
+    }
+}
+
+/// A cardinal direction, read from a `direction` enum field
+#[derive(Copy, Clone)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn vec(self) -> Vec2 {
+        match self {
+            Direction::Up => Vec2::Y,
+            Direction::Down => Vec2::NEG_Y,
+            Direction::Left => Vec2::NEG_X,
+            Direction::Right => Vec2::X,
+        }
+    }
+}
+
+impl FromField for Direction {
+    const EXPECTED: &'static str = "an Up, Down, Left or Right enum";
+
+    fn from_field(value: &FieldValue) -> Option<Self> {
+        match enum_value(value)? {
+            "Up" | "up" => Some(Direction::Up),
+            "Down" | "down" => Some(Direction::Down),
+            "Left" | "left" => Some(Direction::Left),
+            "Right" | "right" => Some(Direction::Right),
+            _ => None,
+        }
+    }
+}
+
+/// Where an actor returns to after falling into a respawning pit
+#[derive(Component, Reflect)]
+pub struct SpawnPoint(pub Vec3);
+
+fn record_spawn_points(
+    mut commands: Commands,
+    actor_query: Query<(Entity, &Transform), (With<Actor>, Without<SpawnPoint>)>,
+) {
+    for (entity, transform) in &actor_query {
+        commands
+            .entity(entity)
+            .insert(SpawnPoint(transform.translation));
+    }
+}
+
+/// A hole that kills whatever falls in, or sends it back to where it started
+#[derive(Component, Reflect)]
+pub struct Pit {
+    pub respawn: bool,
+    /// Damage taken when respawning
+    pub damage: f32,
+}
+
+fn drop_into_pits(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut damage_events: DamageEvents,
+    mut despawn: ResMut<DespawnSet>,
+    pit_query: Query<&Pit>,
+    mut actor_query: Query<
+        (
+            &mut Health,
+            Option<&Resistances>,
+            &mut Transform,
+            &mut Velocity,
+            Option<&SpawnPoint>,
+        ),
+        With<ActorIntent>,
+    >,
+    prop_query: Query<(), With<Prop>>,
+) {
+    for &event in collision_events.read() {
+        let CollisionEvent::Started(entity1, entity2, _) = event else {
+            continue;
+        };
+
+        let mut handle_collision = |pit: Entity, victim: Entity| {
+            let Ok(pit) = pit_query.get(pit) else {
+                return;
+            };
+
+            if prop_query.contains(victim) {
+                despawn.recursive(victim);
+                return;
+            }
+
+            // Corpses just slide over
+            let Ok((mut health, resistances, mut transform, mut velocity, spawn_point)) =
+                actor_query.get_mut(victim)
+            else {
+                return;
+            };
+            if health.current <= 0.0 {
+                return;
+            }
+
+            match spawn_point {
+                Some(spawn_point) if pit.respawn => {
+                    damage_events.deal(
+                        victim,
+                        &mut health,
+                        resistances,
+                        None,
+                        pit.damage,
+                        DamageType::Environmental,
+                    );
+                    transform.translation = spawn_point.0;
+                    velocity.linvel = Vec2::ZERO;
+                },
+                // Resistances don't help against a bottomless fall
+                _ => {
+                    let damage = health.current;
+                    damage_events.deal(
+                        victim,
+                        &mut health,
+                        None,
+                        None,
+                        damage,
+                        DamageType::Environmental,
+                    );
+                },
+            }
+        };
+
+        handle_collision(entity1, entity2);
+        handle_collision(entity2, entity1);
+    }
+}
+
+pub struct PitTemplate {
+    pub transform: Transform,
+    pub size: Vec2,
+    pub respawn: bool,
+    pub damage: f32,
+}
+
+impl PitTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("Pit"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::rgb(0.05, 0.04, 0.06),
+                        custom_size: Some(self.size),
+                        ..default()
+                    },
+                    transform: self.transform,
+                    ..default()
+                },
+                // Shrink the sensor so actors only fall in once they're over the edge
+                Collider::cuboid(
+                    (self.size.x / 2.0 - 4.0).max(1.0),
+                    (self.size.y / 2.0 - 4.0).max(1.0),
+                ),
+                CollisionGroups {
+                    memberships: COLLISION_GROUP,
+                    filters: COLLISION_GROUP,
+                },
+                Sensor,
+                ActiveEvents::COLLISION_EVENTS,
+                Pit {
+                    respawn: self.respawn,
+                    damage: self.damage,
+                },
+            ))
+            .id()
+    }
+}
+
+impl FromLevelEntity for PitTemplate {
+    fn from_level_entity(entity: &LevelEntity) -> Result<Self, FieldError> {
+        Ok(Self {
+            transform: entity.transform,
+            size: Vec2::new(entity.instance.width as f32, entity.instance.height as f32),
+            respawn: entity.field_or("respawn", false)?,
+            damage: entity.field_or("damage", 20.0)?,
+        })
+    }
+}
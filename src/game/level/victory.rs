@@ -2,8 +2,13 @@ use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::common::UpdateSet;
+use crate::game::actor::ally::gather_followers;
+use crate::game::actor::ally::FollowerQuery;
+use crate::game::actor::player::PlayerControl;
+use crate::game::actor::player::Playthrough;
 use crate::game::combat::COLLISION_GROUP;
 use crate::game::combat::PLAYER_HURTBOX_GROUP;
+use crate::game::level::registry::register_entity_spawner;
 
 pub struct VictoryPlugin;
 
@@ -13,6 +18,12 @@ impl Plugin for VictoryPlugin {
 
         app.register_type::<VictorySquare>()
             .add_systems(Update, detect_victory.in_set(UpdateSet::Start));
+        register_entity_spawner(app, "victory", |commands, _, spawn| {
+            Ok(VictorySquareTemplate {
+                transform: spawn.transform,
+            }
+            .spawn(commands))
+        });
     }
 }
 
@@ -26,13 +37,25 @@ pub struct VictorySquare;
 fn detect_victory(
     mut collision_events: EventReader<CollisionEvent>,
     victory_query: Query<(), With<VictorySquare>>,
+    player_query: Query<&GlobalTransform, With<PlayerControl>>,
+    ally_query: FollowerQuery,
+    mut playthrough: ResMut<Playthrough>,
     mut victory: ResMut<Victory>,
 ) {
+    if victory.0 {
+        return;
+    }
+
     for &event in collision_events.read() {
         let CollisionEvent::Started(entity1, entity2, _) = event else {
             continue;
         };
         if victory_query.contains(entity1) || victory_query.contains(entity2) {
+            // Following allies close to Sai escape with them
+            if let Ok(player_gt) = player_query.get_single() {
+                playthrough.escaped_allies +=
+                    gather_followers(player_gt.translation().xy(), &ally_query).len();
+            }
             victory.0 = true;
             break;
         }
@@ -2,20 +2,26 @@ use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 use crate::game::combat::COLLISION_GROUP;
+use crate::game::level::registry::register_tile_spawner;
 
 pub struct WallPlugin;
 
 impl Plugin for WallPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Wall>();
+        register_tile_spawner(app, "wall", |commands, _, transform, size| {
+            WallTemplate { transform, size }.spawn(commands)
+        });
     }
 }
 
 #[derive(Component, Reflect)]
 pub struct Wall;
 
+/// A rectangle of merged wall tiles
 pub struct WallTemplate {
     pub transform: Transform,
+    pub size: Vec2,
 }
 
 impl WallTemplate {
@@ -24,7 +30,7 @@ impl WallTemplate {
             .spawn((
                 Name::new("Wall"),
                 TransformBundle::from_transform(self.transform),
-                Collider::cuboid(8.0, 8.0),
+                Collider::cuboid(self.size.x / 2.0, self.size.y / 2.0),
                 CollisionGroups {
                     memberships: COLLISION_GROUP,
                     filters: COLLISION_GROUP,
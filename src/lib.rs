@@ -8,6 +8,9 @@ mod sequence;
 mod util;
 
 use bevy::prelude::*;
+pub use game::level::floor::FloorSeed;
+pub use sequence::boot::AssetCheckFailed;
+pub use sequence::boot::AssetCheckPlugin;
 
 pub struct AppPlugin;
 
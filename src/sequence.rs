@@ -1,4 +1,4 @@
-mod boot;
+pub mod boot;
 pub mod game;
 
 use bevy::prelude::*;
@@ -8,7 +8,7 @@ use strum::EnumIter;
 
 use crate::common::theme::ThemeBackgroundColor;
 use crate::common::theme::ThemeColor;
-use crate::util::animation::transition::FadeIn;
+use crate::util::animation::transition;
 use crate::util::animation::transition::FadeOut;
 
 pub struct SequencePlugin;
@@ -32,32 +32,24 @@ pub enum SequenceState {
 const FADE_IN_SECS: f32 = 0.1;
 
 fn fade_in(commands: &mut Commands) -> Entity {
-    commands
-        .spawn((
-            Name::new("ScreenFadeIn"),
-            NodeBundle {
-                style: Style {
-                    position_type: PositionType::Absolute,
-                    width: Percent(100.0),
-                    height: Percent(100.0),
-                    ..default()
-                },
-                focus_policy: FocusPolicy::Block,
-                z_index: ZIndex::Global(1000),
-                ..default()
-            },
-            ThemeBackgroundColor(ThemeColor::Body),
-            FadeIn::new(FADE_IN_SECS),
-        ))
-        .id()
+    screen_fade(commands, "ScreenFadeIn", transition::fade_in(FADE_IN_SECS))
 }
 
 const FADE_OUT_SECS: f32 = 0.1;
 
 fn fade_out(commands: &mut Commands, next_state: SequenceState) -> Entity {
+    screen_fade(
+        commands,
+        "ScreenFadeOut",
+        (transition::fade_out(FADE_OUT_SECS), FadeOut { next_state }),
+    )
+}
+
+/// Spawn a full-screen overlay that fades with the given tween
+pub fn screen_fade(commands: &mut Commands, name: &'static str, fade: impl Bundle) -> Entity {
     commands
         .spawn((
-            Name::new("ScreenFadeOut"),
+            Name::new(name),
             NodeBundle {
                 style: Style {
                     position_type: PositionType::Absolute,
@@ -70,7 +62,7 @@ fn fade_out(commands: &mut Commands, next_state: SequenceState) -> Entity {
                 ..default()
             },
             ThemeBackgroundColor(ThemeColor::Body),
-            FadeOut::new(FADE_OUT_SECS, next_state),
+            fade,
         ))
         .id()
 }
@@ -1,9 +1,31 @@
+use bevy::app::AppExit;
+use bevy::asset::RecursiveDependencyLoadState;
 use bevy::prelude::*;
+use bevy::ui::Val::*;
+use bevy_asset_loader::prelude::*;
+use bevy_ecs_ldtk::assets::LdtkProject;
+use bevy_ecs_ldtk::assets::LdtkProjectLoader;
+use bevy_kira_audio::AudioPlugin;
 use iyes_progress::prelude::*;
 
 use crate::common::config::ConfigHandle;
+use crate::common::config::ConfigLoaderPlugin;
+use crate::common::music::MusicAssets;
 use crate::common::window::WindowRoot;
+use crate::game::actor::ActorAssets;
+use crate::game::alarm::AlarmAssets;
+use crate::game::combat::CombatAssets;
+use crate::game::cutscene::CutsceneAssets;
+use crate::game::level::gate::GateAssets;
+use crate::game::level::pickup::PickupAssets;
+use crate::game::level::plate::PlateAssets;
+use crate::game::level::LevelAssets;
+use crate::sequence::game::GameAssets;
 use crate::sequence::SequenceState::*;
+use crate::util::animation::frame::AsepriteSheetLoaderPlugin;
+use crate::util::ui::font::FONT_HANDLE;
+use crate::util::ui::UiRoot;
+use crate::util::vfx::VfxAssets;
 
 pub struct BootStatePlugin;
 
@@ -13,10 +35,8 @@ impl Plugin for BootStatePlugin {
             .add_systems(OnEnter(Boot), enter_boot)
             .add_systems(OnExit(Boot), exit_boot);
 
-        app.add_systems(
-            Update,
-            wait_for_config.track_progress().run_if(in_state(Boot)),
-        );
+        track_collections(app);
+        app.add_systems(Update, check_assets.track_progress().run_if(in_state(Boot)));
     }
 }
 
@@ -36,6 +56,193 @@ fn exit_boot(window_root: Res<WindowRoot>, mut window_query: Query<&mut Window>)
     window.visible = true;
 }
 
-fn wait_for_config(ass: Res<AssetServer>, config_handle: Res<ConfigHandle>) -> Progress {
-    ass.is_loaded_with_dependencies(&config_handle.0).into()
+/// Loads every asset collection and the config without a window or renderer, prints a report,
+/// and exits. Use this instead of `AppPlugin`.
+pub struct AssetCheckPlugin;
+
+impl Plugin for AssetCheckPlugin {
+    fn build(&self, app: &mut App) {
+        // Only the loaders for asset types that appear in a collection
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            ImagePlugin::default_nearest(),
+            AudioPlugin,
+            AsepriteSheetLoaderPlugin,
+            ConfigLoaderPlugin,
+        ))
+        .init_asset::<LdtkProject>()
+        .init_asset_loader::<LdtkProjectLoader>();
+
+        track_collections(app);
+        app.init_resource::<AssetCheckFailed>()
+            .add_systems(Update, report_assets);
+    }
+}
+
+/// Whether `AssetCheckPlugin` found assets that failed to load, to read once the app exits
+#[derive(Resource, Default)]
+pub struct AssetCheckFailed(pub bool);
+
+/// The handles to validate before leaving `Boot`, labeled by their collection
+#[derive(Resource, Default)]
+struct AssetChecklist {
+    handles: Vec<(&'static str, UntypedHandle)>,
+    reported: bool,
+}
+
+impl AssetChecklist {
+    /// The number of handles to check, including the config
+    fn total(&self) -> usize {
+        self.handles.len() + 1
+    }
+
+    /// A description of each asset that failed to load, once every asset is done loading
+    fn failures(&self, config_handle: &ConfigHandle, ass: &AssetServer) -> Option<Vec<String>> {
+        let config = ("Config", config_handle.0.clone().untyped());
+
+        let mut loaded = 0;
+        let mut failures = vec![];
+        for (label, handle) in self.handles.iter().chain([&config]) {
+            match ass.get_recursive_dependency_load_state(handle.id()) {
+                Some(RecursiveDependencyLoadState::Loaded) => loaded += 1,
+                Some(RecursiveDependencyLoadState::Failed) => {
+                    let path = ass
+                        .get_path(handle.id())
+                        .map_or("<unknown path>".to_string(), |x| x.to_string());
+                    failures.push(format!("{label}: \"{path}\" or one of its dependencies"));
+                },
+                _ => {},
+            }
+        }
+
+        (loaded + failures.len() == self.total()).then_some(failures)
+    }
+
+    fn report(&self, failures: &[String]) -> String {
+        format!(
+            "Failed to load {} of {} assets:\n{}",
+            failures.len(),
+            self.total(),
+            failures.join("\n"),
+        )
+    }
+}
+
+fn track_collections(app: &mut App) {
+    app.init_resource::<AssetChecklist>().add_systems(
+        Startup,
+        (
+            track_collection::<ActorAssets>,
+            track_collection::<AlarmAssets>,
+            track_collection::<CombatAssets>,
+            track_collection::<CutsceneAssets>,
+            track_collection::<GameAssets>,
+            track_collection::<GateAssets>,
+            track_collection::<LevelAssets>,
+            track_collection::<MusicAssets>,
+            track_collection::<PickupAssets>,
+            track_collection::<PlateAssets>,
+            track_collection::<VfxAssets>,
+        ),
+    );
+}
+
+fn track_collection<T: AssetCollection + TypePath>(world: &mut World) {
+    // The asset server hands back the same handles if `init_collection` already loaded them
+    let handles = T::load(world);
+    world
+        .resource_mut::<AssetChecklist>()
+        .handles
+        .extend(handles.into_iter().map(|x| (T::short_type_path(), x)));
+}
+
+fn check_assets(
+    mut commands: Commands,
+    mut checklist: ResMut<AssetChecklist>,
+    config_handle: Res<ConfigHandle>,
+    ass: Res<AssetServer>,
+    ui_root: Res<UiRoot>,
+    window_root: Res<WindowRoot>,
+    mut window_query: Query<&mut Window>,
+) -> Progress {
+    if checklist.reported {
+        return false.into();
+    }
+    let Some(failures) = checklist.failures(&config_handle, &ass) else {
+        return false.into();
+    };
+    if failures.is_empty() {
+        return true.into();
+    }
+    checklist.reported = true;
+
+    let report = checklist.report(&failures);
+    error!("{report}");
+
+    // Stay in `Boot` and show the report instead of running on with missing assets
+    if let Ok(mut window) = window_query.get_mut(window_root.primary) {
+        window.visible = true;
+    }
+    let error_screen = ErrorScreenTemplate { message: report }.spawn(&mut commands);
+    commands.entity(error_screen).set_parent(ui_root.body);
+
+    false.into()
+}
+
+fn report_assets(
+    mut app_exit: EventWriter<AppExit>,
+    mut checklist: ResMut<AssetChecklist>,
+    mut failed: ResMut<AssetCheckFailed>,
+    config_handle: Res<ConfigHandle>,
+    ass: Res<AssetServer>,
+) {
+    if checklist.reported {
+        return;
+    }
+    let Some(failures) = checklist.failures(&config_handle, &ass) else {
+        return;
+    };
+    checklist.reported = true;
+
+    if failures.is_empty() {
+        println!("All {} assets loaded", checklist.total());
+    } else {
+        eprintln!("{}", checklist.report(&failures));
+        failed.0 = true;
+    }
+    app_exit.send(AppExit);
+}
+
+struct ErrorScreenTemplate {
+    message: String,
+}
+
+impl ErrorScreenTemplate {
+    fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("ErrorScreen"),
+                TextBundle {
+                    text: Text::from_section(
+                        self.message,
+                        TextStyle {
+                            font: FONT_HANDLE,
+                            font_size: 20.0,
+                            color: Color::WHITE,
+                        },
+                    ),
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        width: Percent(100.0),
+                        height: Percent(100.0),
+                        padding: UiRect::all(Px(32.0)),
+                        ..default()
+                    },
+                    background_color: Color::BLACK.into(),
+                    ..default()
+                },
+            ))
+            .id()
+    }
 }
@@ -8,15 +8,19 @@ use leafwing_input_manager::prelude::*;
 
 use crate::common::camera::CameraRoot;
 use crate::common::UpdateSet;
+use crate::game::actor::bark::BarkEvent;
 use crate::game::actor::enemy::AlertEvent;
+use crate::game::actor::inventory::InventoryBarTemplate;
 use crate::game::actor::player::Playthrough;
 use crate::game::alarm::Alarm;
 use crate::game::alarm::AlarmAssets;
 use crate::game::alarm::AlarmMeterTemplate;
+use crate::game::combat::DamageDealt;
 use crate::game::combat::DeathEvent;
 use crate::game::combat::HitEvent;
 use crate::game::cutscene::CutsceneAssets;
 use crate::game::cutscene::CutsceneTemplate;
+use crate::game::level::exit::VisitedLevels;
 use crate::game::level::victory::Victory;
 use crate::game::level::LevelAssets;
 use crate::game::level::LevelTemplate;
@@ -66,7 +70,7 @@ impl Plugin for GameStatePlugin {
 
 #[derive(AssetCollection, Resource, Reflect, Default)]
 #[reflect(Resource)]
-struct GameAssets {
+pub struct GameAssets {
     #[asset(path = "sound/sfx/pop_1.wav")]
     sfx_restart: Handle<AudioSource>,
 }
@@ -105,6 +109,8 @@ fn enter_game(
     // Spawn HUD
     let alarm_meter = AlarmMeterTemplate.spawn(&mut commands, &alarm_assets);
     commands.entity(alarm_meter).set_parent(ui_root.body);
+    let inventory_bar = InventoryBarTemplate.spawn(&mut commands);
+    commands.entity(inventory_bar).set_parent(ui_root.body);
 
     // Spawn cutscene only on the first run
     if !*seen_cutscene {
@@ -122,11 +128,14 @@ fn exit_game(
     camera_root: Res<CameraRoot>,
     mut collision_events: ResMut<Events<CollisionEvent>>,
     mut hit_events: ResMut<Events<HitEvent>>,
+    mut damage_events: ResMut<Events<DamageDealt>>,
     mut death_events: ResMut<Events<DeathEvent>>,
     mut detect_events: ResMut<Events<AlertEvent>>,
+    mut bark_events: ResMut<Events<BarkEvent>>,
     mut level_selection: ResMut<LevelSelection>,
     mut playthrough: ResMut<Playthrough>,
     mut victory: ResMut<Victory>,
+    mut visited_levels: ResMut<VisitedLevels>,
     mut alarm: ResMut<Alarm>,
     mut camera_query: Query<&mut Transform>,
 ) {
@@ -134,13 +143,16 @@ fn exit_game(
     *level_selection = default();
     *playthrough = default();
     *victory = default();
+    *visited_levels = default();
     *alarm = default();
 
     // Clear events
     collision_events.clear();
     hit_events.clear();
+    damage_events.clear();
     death_events.clear();
     detect_events.clear();
+    bark_events.clear();
 
     // Despawn entities
     commands.entity(ui_root.body).despawn_descendants();
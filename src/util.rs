@@ -7,6 +7,7 @@ pub use crate::util::despawn::DespawnSet;
 
 pub mod animation;
 mod despawn;
+pub mod grid;
 pub mod math;
 pub mod ui;
 pub mod vfx;
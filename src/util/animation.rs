@@ -1,8 +1,10 @@
 pub mod facing;
 pub mod follow;
+pub mod frame;
 pub mod lifetime;
 pub mod offset;
 pub mod transition;
+pub mod tween;
 
 use bevy::prelude::*;
 
@@ -13,9 +15,11 @@ impl Plugin for AnimationPlugin {
         app.add_plugins((
             facing::FacingPlugin,
             follow::FollowPlugin,
+            frame::FramePlugin,
             lifetime::LifetimePlugin,
             offset::OffsetPlugin,
             transition::TransitionPlugin,
+            tween::TweenPlugin,
         ));
     }
 }
@@ -0,0 +1,294 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_common_assets::json::JsonAssetPlugin;
+use serde::Deserialize;
+
+pub struct FramePlugin;
+
+impl Plugin for FramePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(AsepriteSheetLoaderPlugin)
+            .init_resource::<AsepriteAtlases>()
+            .add_systems(
+                PreUpdate,
+                build_aseprite_atlases.run_if(on_event::<AssetEvent<AsepriteSheet>>()),
+            );
+
+        app.register_type::<FrameAnimation>()
+            .add_systems(PostUpdate, advance_frame_animations);
+    }
+}
+
+/// Loads `AsepriteSheet`s without building atlases, for apps without a renderer
+pub struct AsepriteSheetLoaderPlugin;
+
+impl Plugin for AsepriteSheetLoaderPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<AsepriteSheet>()
+            .add_plugins(JsonAssetPlugin::<AsepriteSheet>::new(&["aseprite.json"]));
+    }
+}
+
+/// A sprite sheet exported from Aseprite with `--format json-array --list-tags`
+#[derive(Asset, Reflect, Deserialize)]
+#[reflect(from_reflect = false)]
+pub struct AsepriteSheet {
+    frames: Vec<AsepriteFrame>,
+    meta: AsepriteMeta,
+}
+
+#[derive(Reflect, Deserialize)]
+struct AsepriteFrame {
+    frame: AsepriteRect,
+    /// In milliseconds
+    duration: u32,
+}
+
+#[derive(Reflect, Deserialize)]
+struct AsepriteRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Reflect, Deserialize)]
+struct AsepriteMeta {
+    size: AsepriteSize,
+    #[serde(rename = "frameTags", default)]
+    frame_tags: Vec<AsepriteTag>,
+}
+
+#[derive(Reflect, Deserialize)]
+struct AsepriteSize {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Reflect, Deserialize)]
+struct AsepriteTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: String,
+}
+
+impl AsepriteSheet {
+    /// The clip for each tag, keyed by lowercase tag name
+    fn clips(&self) -> HashMap<String, Vec<(usize, f32)>> {
+        let mut clips = HashMap::default();
+        for tag in &self.meta.frame_tags {
+            let Some(clip) = self.clip(tag) else {
+                continue;
+            };
+            // Tag names can repeat, in which case the first one wins
+            clips.entry(tag.name.to_lowercase()).or_insert(clip);
+        }
+        clips
+    }
+
+    /// The atlas indices and durations (in seconds) of the frames in the given tag
+    fn clip(&self, tag: &AsepriteTag) -> Option<Vec<(usize, f32)>> {
+        let to = tag.to.min(self.frames.len().checked_sub(1)?);
+
+        let forward = (tag.from..=to).collect::<Vec<_>>();
+        let indices = match tag.direction.as_str() {
+            "reverse" => forward.into_iter().rev().collect(),
+            "pingpong" => {
+                let back = forward
+                    .iter()
+                    .rev()
+                    .skip(1)
+                    .take(forward.len().saturating_sub(2));
+                forward.iter().chain(back).copied().collect()
+            },
+            _ => forward,
+        };
+
+        Some(
+            indices
+                .into_iter()
+                .map(|i| (i, self.frames[i].duration as f32 / 1000.0))
+                .collect(),
+        )
+    }
+
+    fn layout(&self) -> TextureAtlasLayout {
+        let size = Vec2::new(self.meta.size.w as f32, self.meta.size.h as f32);
+        let mut layout = TextureAtlasLayout::new_empty(size);
+        for frame in &self.frames {
+            let rect = &frame.frame;
+            layout.add_texture(Rect::new(
+                rect.x as f32,
+                rect.y as f32,
+                (rect.x + rect.w) as f32,
+                (rect.y + rect.h) as f32,
+            ));
+        }
+        layout
+    }
+}
+
+/// The atlas layout and clips built for a loaded `AsepriteSheet`
+struct AsepriteAtlas {
+    layout: Handle<TextureAtlasLayout>,
+    clips: HashMap<String, Vec<(usize, f32)>>,
+}
+
+#[derive(Resource, Default)]
+struct AsepriteAtlases(HashMap<AssetId<AsepriteSheet>, AsepriteAtlas>);
+
+fn build_aseprite_atlases(
+    mut sheet_events: EventReader<AssetEvent<AsepriteSheet>>,
+    sheets: Res<Assets<AsepriteSheet>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut atlases: ResMut<AsepriteAtlases>,
+) {
+    for event in sheet_events.read() {
+        match *event {
+            // Rebuild on modification so artists can re-export while the game is running
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => {
+                let Some(sheet) = sheets.get(id) else {
+                    continue;
+                };
+                atlases.0.insert(
+                    id,
+                    AsepriteAtlas {
+                        layout: layouts.add(sheet.layout()),
+                        clips: sheet.clips(),
+                    },
+                );
+            },
+            AssetEvent::Removed { id } => {
+                atlases.0.remove(&id);
+            },
+            _ => {},
+        }
+    }
+}
+
+#[derive(Reflect, Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum FrameClip {
+    #[default]
+    Idle,
+    Walk,
+    Attack,
+    Hurt,
+    Die,
+}
+
+impl FrameClip {
+    /// The Aseprite tag to play for this clip (case-insensitive, so lowercase here)
+    fn tag(self) -> &'static str {
+        match self {
+            FrameClip::Idle => "idle",
+            FrameClip::Walk => "walk",
+            FrameClip::Attack => "attack",
+            FrameClip::Hurt => "hurt",
+            FrameClip::Die => "die",
+        }
+    }
+
+    fn is_looping(self) -> bool {
+        matches!(self, FrameClip::Idle | FrameClip::Walk)
+    }
+}
+
+/// Plays named clips from an `AsepriteSheet` on this entity's `TextureAtlas`
+#[derive(Component, Reflect)]
+pub struct FrameAnimation {
+    pub sheet: Handle<AsepriteSheet>,
+    clip: FrameClip,
+    step: usize,
+    t: f32,
+}
+
+impl FrameAnimation {
+    pub fn new(sheet: Handle<AsepriteSheet>) -> Self {
+        Self {
+            sheet,
+            clip: FrameClip::Idle,
+            step: 0,
+            t: 0.0,
+        }
+    }
+
+    pub fn clip(&self) -> FrameClip {
+        self.clip
+    }
+
+    /// Switch to a clip, restarting it only if it's not already playing
+    pub fn play(&mut self, clip: FrameClip) {
+        if self.clip != clip {
+            self.clip = clip;
+            self.step = 0;
+            self.t = 0.0;
+        }
+    }
+}
+
+fn advance_frame_animations(
+    mut commands: Commands,
+    mut animation_query: Query<(Entity, &mut FrameAnimation, Option<&mut TextureAtlas>)>,
+    atlases: Res<AsepriteAtlases>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+
+    for (entity, mut anim, atlas) in &mut animation_query {
+        let Some(AsepriteAtlas { layout, clips }) = atlases.0.get(&anim.sheet.id()) else {
+            continue;
+        };
+        // Sheets without this clip fall back to idle
+        let Some(frames) = clips
+            .get(anim.clip.tag())
+            .or_else(|| clips.get(FrameClip::Idle.tag()))
+            .filter(|frames| !frames.is_empty())
+        else {
+            continue;
+        };
+
+        // Avoid spinning forever on zero-length frames
+        let can_loop = anim.clip.is_looping() && frames.iter().any(|&(_, duration)| duration > 0.0);
+
+        anim.step = anim.step.min(frames.len() - 1);
+        anim.t += dt;
+        loop {
+            let duration = frames[anim.step].1;
+            if anim.t < duration {
+                break;
+            }
+
+            if anim.step + 1 < frames.len() {
+                anim.t -= duration;
+                anim.step += 1;
+            } else if can_loop {
+                anim.t -= duration;
+                anim.step = 0;
+            } else {
+                // Hold the last frame
+                anim.t = duration;
+                break;
+            }
+        }
+        let index = frames[anim.step].0;
+
+        match atlas {
+            Some(mut atlas) => {
+                if atlas.index != index {
+                    atlas.index = index;
+                }
+                if atlas.layout != *layout {
+                    atlas.layout = layout.clone();
+                }
+            },
+            None => {
+                commands.entity(entity).insert(TextureAtlas {
+                    layout: layout.clone(),
+                    index,
+                });
+            },
+        }
+    }
+}
@@ -1,88 +1,63 @@
 use bevy::prelude::*;
-use bevy::ui::Val::*;
 
-use crate::common::theme::ThemeBackgroundColor;
-use crate::common::theme::ThemeColor;
 use crate::common::PostColorSet;
 use crate::sequence::SequenceState;
-use crate::util::DespawnSet;
+use crate::util::animation::tween::BackgroundColorLens;
+use crate::util::animation::tween::Ease;
+use crate::util::animation::tween::Tween;
+use crate::util::animation::tween::TweenFinished;
 
 pub struct TransitionPlugin;
 
 impl Plugin for TransitionPlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<FadeIn>()
-            .add_systems(PostUpdate, apply_fade_in.in_set(PostColorSet::Blend));
-
         app.register_type::<FadeOut>()
-            .add_systems(PostUpdate, apply_fade_out.in_set(PostColorSet::Blend));
+            .add_systems(PostUpdate, finish_fade_out.after(PostColorSet::Blend));
     }
 }
 
-#[derive(Component, Reflect)]
-pub struct FadeIn {
-    duration: f32,
-    remaining: f32,
+/// Fade a node in from its background color, despawning it once it's transparent.
+/// Fades run on real time so they keep going while the game is paused.
+pub fn fade_in(duration: f32) -> Tween<BackgroundColorLens> {
+    Tween::new(
+        BackgroundColorLens::alpha(1.0, 0.0),
+        duration,
+        Ease::InOutSine,
+    )
+    .with_real_time()
+    .despawn_on_finish()
 }
 
-impl FadeIn {
-    pub fn new(duration: f32) -> Self {
-        Self {
-            duration,
-            remaining: duration,
-        }
-    }
+/// Fade a node out to its background color, despawning it once it's opaque
+pub fn fade_out(duration: f32) -> Tween<BackgroundColorLens> {
+    fade_out_and_hold(duration).despawn_on_finish()
 }
 
-fn apply_fade_in(
-    time: Res<Time>,
-    mut despawn: ResMut<DespawnSet>,
-    mut fade_query: Query<(Entity, &mut FadeIn, &mut BackgroundColor)>,
-) {
-    let dt = time.delta_seconds();
-    for (entity, mut fade, mut color) in &mut fade_query {
-        // TODO: Non-linear alpha?
-        color.0.set_a((fade.remaining / fade.duration).max(0.0));
-        if fade.remaining <= 0.0 {
-            despawn.recursive(entity);
-        }
-        fade.remaining -= dt;
-    }
+/// Fade a node out to its background color and keep it, e.g. to cover a loading screen until
+/// replacing the tween with `fade_in`
+pub fn fade_out_and_hold(duration: f32) -> Tween<BackgroundColorLens> {
+    Tween::new(
+        BackgroundColorLens::alpha(0.0, 1.0),
+        duration,
+        Ease::InOutSine,
+    )
+    .with_real_time()
 }
 
+/// Switches to `next_state` once the entity's `fade_out` tween finishes
 #[derive(Component, Reflect)]
 pub struct FadeOut {
-    duration: f32,
-    remaining: f32,
-    next_state: SequenceState,
-}
-
-impl FadeOut {
-    pub fn new(duration: f32, next_state: SequenceState) -> Self {
-        Self {
-            duration,
-            remaining: duration,
-            next_state,
-        }
-    }
+    pub next_state: SequenceState,
 }
 
-fn apply_fade_out(
-    time: Res<Time>,
-    mut despawn: ResMut<DespawnSet>,
+fn finish_fade_out(
+    mut finished_events: EventReader<TweenFinished>,
     mut next_state: ResMut<NextState<SequenceState>>,
-    mut fade_query: Query<(Entity, &mut FadeOut, &mut BackgroundColor)>,
+    fade_query: Query<&FadeOut>,
 ) {
-    let dt = time.delta_seconds();
-    for (entity, mut fade, mut color) in &mut fade_query {
-        // TODO: Non-linear alpha?
-        color
-            .0
-            .set_a(1.0 - (fade.remaining / fade.duration).max(0.0));
-        if fade.remaining <= 0.0 {
+    for event in finished_events.read() {
+        if let Ok(fade) = fade_query.get(event.entity) {
             next_state.set(fade.next_state);
-            despawn.recursive(entity);
         }
-        fade.remaining -= dt;
     }
 }
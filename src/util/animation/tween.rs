@@ -0,0 +1,453 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+use crate::common::PostColorSet;
+use crate::common::PostTransformSet;
+use crate::util::DespawnSet;
+
+pub struct TweenPlugin;
+
+impl Plugin for TweenPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Ease>()
+            .register_type::<Repeat>()
+            .add_event::<TweenFinished>();
+
+        add_tween::<TranslationLens>(app, PostTransformSet::Blend);
+        add_tween::<RotationLens>(app, PostTransformSet::Blend);
+        add_tween::<ScaleLens>(app, PostTransformSet::Blend);
+        add_tween::<SpriteColorLens>(app, PostColorSet::Blend);
+        add_tween::<BackgroundColorLens>(app, PostColorSet::Blend);
+        add_tween::<TextColorLens>(app, PostColorSet::Blend);
+        add_tween::<AudioVolumeLens>(app, PostColorSet::Blend);
+
+        app.register_type::<AudioVolume>()
+            .add_systems(PostUpdate, apply_audio_volume.after(PostColorSet::Blend));
+    }
+}
+
+/// Tick and apply `Tween<L>` in the given `PostUpdate` set
+pub fn add_tween<L: Lens>(app: &mut App, set: impl SystemSet) {
+    app.add_systems(
+        PostUpdate,
+        (tick_tweens::<L>, apply_tweens::<L>).chain().in_set(set),
+    );
+}
+
+/// Easing curves mapping linear progress in [0, 1] to eased progress
+#[derive(Reflect, Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum Ease {
+    #[default]
+    Linear,
+    InQuad,
+    OutQuad,
+    InOutQuad,
+    InCubic,
+    OutCubic,
+    InOutCubic,
+    InSine,
+    OutSine,
+    InOutSine,
+    /// Overshoots slightly before settling
+    OutBack,
+    /// Rises to 1 at the halfway point and falls back to 0
+    Arch,
+}
+
+impl Ease {
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Ease::Linear => t,
+            Ease::InQuad => t * t,
+            Ease::OutQuad => 1.0 - (1.0 - t) * (1.0 - t),
+            Ease::InOutQuad => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            },
+            Ease::InCubic => t * t * t,
+            Ease::OutCubic => 1.0 - (1.0 - t).powi(3),
+            Ease::InOutCubic => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            },
+            Ease::InSine => 1.0 - (t * PI / 2.0).cos(),
+            Ease::OutSine => (t * PI / 2.0).sin(),
+            Ease::InOutSine => -((t * PI).cos() - 1.0) / 2.0,
+            Ease::OutBack => {
+                const C1: f32 = 1.70158;
+                const C3: f32 = C1 + 1.0;
+                1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+            },
+            Ease::Arch => (t * PI).sin(),
+        }
+    }
+}
+
+#[derive(Reflect, Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum Repeat {
+    /// Play through the steps once and hold the final value
+    #[default]
+    Once,
+    /// Restart from the first step forever
+    Loop,
+    /// Play forward, then backward, forever
+    PingPong,
+}
+
+/// Maps eased progress onto a component.
+///
+/// Transform lenses blend onto the transform (add translation, add rotation, multiply scale),
+/// so the target should be reset every frame, e.g. with `Offset`. Color and volume lenses
+/// overwrite their target outright.
+pub trait Lens: Send + Sync + 'static {
+    type Target: Component;
+
+    fn apply(&self, target: &mut Self::Target, t: f32);
+}
+
+pub struct TweenStep<L> {
+    pub lens: L,
+    pub duration: f32,
+    pub ease: Ease,
+}
+
+/// Animates a component through a sequence of lenses
+#[derive(Component)]
+pub struct Tween<L: Lens> {
+    steps: Vec<TweenStep<L>>,
+    step: usize,
+    elapsed: f32,
+    delay: f32,
+    repeat: Repeat,
+    forward: bool,
+    real_time: bool,
+    despawn_on_finish: bool,
+    tag: u32,
+    finished: bool,
+}
+
+impl<L: Lens> Tween<L> {
+    pub fn new(lens: L, duration: f32, ease: Ease) -> Self {
+        Self {
+            steps: vec![TweenStep {
+                lens,
+                duration,
+                ease,
+            }],
+            step: 0,
+            elapsed: 0.0,
+            delay: 0.0,
+            repeat: Repeat::Once,
+            forward: true,
+            real_time: false,
+            despawn_on_finish: false,
+            tag: 0,
+            finished: false,
+        }
+    }
+
+    /// Play another step after the previous one finishes
+    pub fn then(mut self, lens: L, duration: f32, ease: Ease) -> Self {
+        self.steps.push(TweenStep {
+            lens,
+            duration,
+            ease,
+        });
+        self
+    }
+
+    /// Hold the first step's starting value for a while before playing
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn with_repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Keep playing while virtual time is paused
+    pub fn with_real_time(mut self) -> Self {
+        self.real_time = true;
+        self
+    }
+
+    /// Tag the `TweenFinished` event, to tell tweens on the same entity apart
+    pub fn with_tag(mut self, tag: u32) -> Self {
+        self.tag = tag;
+        self
+    }
+
+    pub fn despawn_on_finish(mut self) -> Self {
+        self.despawn_on_finish = true;
+        self
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Start out finished, holding the last step's final value
+    pub fn skip_to_end(mut self) -> Self {
+        self.step = self.steps.len() - 1;
+        self.elapsed = self.steps[self.step].duration;
+        self.finished = true;
+        self
+    }
+
+    /// Restart from the beginning
+    pub fn restart(&mut self) {
+        self.step = 0;
+        self.elapsed = 0.0;
+        self.forward = true;
+        self.finished = false;
+    }
+
+    fn progress(&self) -> f32 {
+        let duration = self.steps[self.step].duration;
+        let t = if duration > 0.0 {
+            (self.elapsed / duration).min(1.0)
+        } else {
+            1.0
+        };
+        if self.forward {
+            t
+        } else {
+            1.0 - t
+        }
+    }
+
+    /// Advance by `dt` seconds, returning true if the tween just finished
+    fn tick(&mut self, mut dt: f32) -> bool {
+        if self.finished {
+            return false;
+        }
+
+        let delay = self.delay.min(dt);
+        self.delay -= delay;
+        dt -= delay;
+
+        self.elapsed += dt;
+        while self.elapsed >= self.steps[self.step].duration {
+            self.elapsed -= self.steps[self.step].duration;
+
+            let last = if self.forward {
+                self.step + 1 == self.steps.len()
+            } else {
+                self.step == 0
+            };
+            if !last {
+                if self.forward {
+                    self.step += 1;
+                } else {
+                    self.step -= 1;
+                }
+                continue;
+            }
+
+            match self.repeat {
+                Repeat::Once => {
+                    self.elapsed = self.steps[self.step].duration;
+                    self.finished = true;
+                    return true;
+                },
+                Repeat::Loop => self.step = 0,
+                Repeat::PingPong => self.forward = !self.forward,
+            }
+
+            // Avoid spinning forever on zero-length steps
+            if self.steps.iter().all(|step| step.duration <= 0.0) {
+                self.elapsed = 0.0;
+                break;
+            }
+        }
+
+        false
+    }
+}
+
+/// Sent when a non-repeating tween plays its last step to the end
+#[derive(Event)]
+pub struct TweenFinished {
+    pub entity: Entity,
+    pub tag: u32,
+}
+
+fn tick_tweens<L: Lens>(
+    mut despawn: ResMut<DespawnSet>,
+    mut finished_events: EventWriter<TweenFinished>,
+    mut tween_query: Query<(Entity, &mut Tween<L>)>,
+    time: Res<Time>,
+    real_time: Res<Time<Real>>,
+) {
+    for (entity, mut tween) in &mut tween_query {
+        let dt = if tween.real_time {
+            real_time.delta_seconds()
+        } else {
+            time.delta_seconds()
+        };
+        if !tween.tick(dt) {
+            continue;
+        }
+
+        finished_events.send(TweenFinished {
+            entity,
+            tag: tween.tag,
+        });
+        if tween.despawn_on_finish {
+            despawn.recursive(entity);
+        }
+    }
+}
+
+fn apply_tweens<L: Lens>(mut tween_query: Query<(&Tween<L>, &mut L::Target)>) {
+    for (tween, mut target) in &mut tween_query {
+        let step = &tween.steps[tween.step];
+        step.lens
+            .apply(&mut target, step.ease.apply(tween.progress()));
+    }
+}
+
+pub struct TranslationLens {
+    pub start: Vec3,
+    pub end: Vec3,
+}
+
+impl Lens for TranslationLens {
+    type Target = Transform;
+
+    fn apply(&self, target: &mut Transform, t: f32) {
+        target.translation += self.start.lerp(self.end, t);
+    }
+}
+
+/// Rotation about the Z axis, in radians
+pub struct RotationLens {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl Lens for RotationLens {
+    type Target = Transform;
+
+    fn apply(&self, target: &mut Transform, t: f32) {
+        target.rotation *= Quat::from_rotation_z(self.start + (self.end - self.start) * t);
+    }
+}
+
+pub struct ScaleLens {
+    pub start: Vec3,
+    pub end: Vec3,
+}
+
+impl Lens for ScaleLens {
+    type Target = Transform;
+
+    fn apply(&self, target: &mut Transform, t: f32) {
+        target.scale *= self.start.lerp(self.end, t);
+    }
+}
+
+fn lerp_color(start: Color, end: Color, t: f32) -> Color {
+    let start = Vec4::from(start.as_rgba_f32());
+    let end = Vec4::from(end.as_rgba_f32());
+    Color::from(start.lerp(end, t))
+}
+
+pub struct SpriteColorLens {
+    pub start: Color,
+    pub end: Color,
+}
+
+impl Lens for SpriteColorLens {
+    type Target = Sprite;
+
+    fn apply(&self, target: &mut Sprite, t: f32) {
+        target.color = lerp_color(self.start, self.end, t);
+    }
+}
+
+pub struct BackgroundColorLens {
+    pub start: Color,
+    pub end: Color,
+    /// Only tween the alpha, keeping the existing color
+    pub alpha_only: bool,
+}
+
+impl BackgroundColorLens {
+    /// Fade the existing background color's alpha
+    pub fn alpha(start: f32, end: f32) -> Self {
+        Self {
+            start: Color::NONE.with_a(start),
+            end: Color::NONE.with_a(end),
+            alpha_only: true,
+        }
+    }
+}
+
+impl Lens for BackgroundColorLens {
+    type Target = BackgroundColor;
+
+    fn apply(&self, target: &mut BackgroundColor, t: f32) {
+        let color = lerp_color(self.start, self.end, t);
+        if self.alpha_only {
+            target.0.set_a(color.a());
+        } else {
+            target.0 = color;
+        }
+    }
+}
+
+pub struct TextColorLens {
+    pub section: usize,
+    pub start: Color,
+    pub end: Color,
+}
+
+impl Lens for TextColorLens {
+    type Target = Text;
+
+    fn apply(&self, target: &mut Text, t: f32) {
+        if let Some(section) = target.sections.get_mut(self.section) {
+            section.style.color = lerp_color(self.start, self.end, t);
+        }
+    }
+}
+
+/// The volume to keep this entity's `Handle<AudioInstance>` at
+#[derive(Component, Reflect)]
+pub struct AudioVolume(pub f64);
+
+fn apply_audio_volume(
+    volume_query: Query<(&AudioVolume, &Handle<AudioInstance>), Changed<AudioVolume>>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    for (volume, handle) in &volume_query {
+        if let Some(instance) = audio_instances.get_mut(handle) {
+            instance.set_volume(volume.0, AudioTween::default());
+        }
+    }
+}
+
+pub struct AudioVolumeLens {
+    pub start: f64,
+    pub end: f64,
+}
+
+impl Lens for AudioVolumeLens {
+    type Target = AudioVolume;
+
+    fn apply(&self, target: &mut AudioVolume, t: f32) {
+        target.0 = self.start + (self.end - self.start) * t as f64;
+    }
+}
@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+/// Cover a set of grid cells with as few non-overlapping rectangles as a greedy pass allows.
+/// Each rectangle's `max` is exclusive, and the output order is deterministic.
+pub fn merge_cells(cells: &HashSet<IVec2>) -> Vec<IRect> {
+    // Scan bottom to top, left to right, so each rectangle starts at its bottom-left corner
+    let mut sorted = cells.iter().copied().collect::<Vec<_>>();
+    sorted.sort_by_key(|cell| (cell.y, cell.x));
+
+    let mut covered = HashSet::default();
+    let mut rects = vec![];
+    for min in sorted {
+        if covered.contains(&min) {
+            continue;
+        }
+        let free = |cell: IVec2| cells.contains(&cell) && !covered.contains(&cell);
+
+        // Extend right as far as possible, then extend up while every cell in the next row is free
+        let mut max = min + IVec2::ONE;
+        while free(IVec2::new(max.x, min.y)) {
+            max.x += 1;
+        }
+        while (min.x..max.x).all(|x| free(IVec2::new(x, max.y))) {
+            max.y += 1;
+        }
+
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                covered.insert(IVec2::new(x, y));
+            }
+        }
+        rects.push(IRect::from_corners(min, max));
+    }
+
+    rects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse rows of `#` (filled) and `.` (empty), with the first row on top
+    fn grid(rows: &[&str]) -> HashSet<IVec2> {
+        let height = rows.len() as i32;
+        rows.iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                let y = height - 1 - row as i32;
+                line.chars()
+                    .enumerate()
+                    .filter(|&(_, c)| c == '#')
+                    .map(move |(x, _)| IVec2::new(x as i32, y))
+            })
+            .collect()
+    }
+
+    /// Every cell is covered exactly once, and nothing else is covered
+    fn assert_exact_cover(cells: &HashSet<IVec2>, rects: &[IRect]) {
+        let mut covered = HashSet::default();
+        for rect in rects {
+            for y in rect.min.y..rect.max.y {
+                for x in rect.min.x..rect.max.x {
+                    let cell = IVec2::new(x, y);
+                    assert!(cells.contains(&cell), "{cell} is not a filled cell");
+                    assert!(covered.insert(cell), "{cell} is covered twice");
+                }
+            }
+        }
+        assert_eq!(covered.len(), cells.len());
+    }
+
+    #[test]
+    fn empty() {
+        assert!(merge_cells(&HashSet::default()).is_empty());
+    }
+
+    #[test]
+    fn single_cell() {
+        let cells = grid(&["#"]);
+        assert_eq!(
+            merge_cells(&cells),
+            [IRect::from_corners(IVec2::ZERO, IVec2::ONE)],
+        );
+    }
+
+    #[test]
+    fn row_and_column() {
+        let cells = grid(&["####"]);
+        assert_eq!(merge_cells(&cells), [IRect::new(0, 0, 4, 1)]);
+
+        let cells = grid(&["#", "#", "#"]);
+        assert_eq!(merge_cells(&cells), [IRect::new(0, 0, 1, 3)]);
+    }
+
+    #[test]
+    fn filled_rectangle() {
+        let cells = grid(&["###", "###"]);
+        assert_eq!(merge_cells(&cells), [IRect::new(0, 0, 3, 2)]);
+    }
+
+    #[test]
+    fn l_shape() {
+        let cells = grid(&["#..", "#..", "###"]);
+        let rects = merge_cells(&cells);
+        assert_exact_cover(&cells, &rects);
+        assert_eq!(rects, [IRect::new(0, 0, 3, 1), IRect::new(0, 1, 1, 3)]);
+    }
+
+    #[test]
+    fn room_outline() {
+        let cells = grid(&["#####", "#...#", "#...#", "#####"]);
+        let rects = merge_cells(&cells);
+        assert_exact_cover(&cells, &rects);
+        assert_eq!(rects.len(), 4);
+    }
+
+    #[test]
+    fn disjoint_and_irregular() {
+        let cells = grid(&["##..#", ".##.#", "..###", "#...."]);
+        let rects = merge_cells(&cells);
+        assert_exact_cover(&cells, &rects);
+    }
+
+    #[test]
+    fn negative_coordinates() {
+        let cells = [IVec2::new(-2, -1), IVec2::new(-1, -1)]
+            .into_iter()
+            .collect::<HashSet<_>>();
+        assert_eq!(merge_cells(&cells), [IRect::new(-2, -1, 0, 0)]);
+    }
+}
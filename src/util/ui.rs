@@ -3,6 +3,8 @@ pub mod font;
 pub mod health_bar;
 pub mod interaction;
 pub mod nametag;
+pub mod speech_bubble;
+pub mod status_icons;
 
 use bevy::prelude::*;
 use bevy::ui::Val::*;
@@ -18,6 +20,8 @@ impl Plugin for UiPlugin {
             font::FontPlugin,
             health_bar::HealthBarPlugin,
             interaction::InteractionPlugin,
+            speech_bubble::SpeechBubblePlugin,
+            status_icons::StatusIconsPlugin,
         ));
     }
 }
@@ -0,0 +1,79 @@
+use bevy::math::vec2;
+use bevy::prelude::*;
+
+use crate::util::animation::lifetime::Lifetime;
+use crate::util::ui::backdrop::BackdropTemplate;
+use crate::util::ui::font::FONT_HANDLE;
+
+pub struct SpeechBubblePlugin;
+
+impl Plugin for SpeechBubblePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SpeechBubble>();
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct SpeechBubble;
+
+pub struct SpeechBubbleTemplate {
+    pub offset: Transform,
+    pub text: String,
+    pub lifetime: f32,
+}
+
+impl Default for SpeechBubbleTemplate {
+    fn default() -> Self {
+        Self {
+            offset: default(),
+            text: String::new(),
+            lifetime: 2.0,
+        }
+    }
+}
+
+impl SpeechBubbleTemplate {
+    const TEXT_COLOR: Color = Color::rgba(1.0, 1.0, 0.95, 0.9);
+    // Rough average glyph width after scaling, used to fit the backdrop to the text
+    const CHAR_WIDTH: f32 = 1.9;
+    const PADDING: f32 = 4.0;
+
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        let style = TextStyle {
+            font: FONT_HANDLE,
+            font_size: 16.0,
+            color: Self::TEXT_COLOR,
+        };
+        let width = Self::CHAR_WIDTH * self.text.chars().count() as f32 + Self::PADDING;
+
+        // Children
+        let text = commands
+            .spawn((
+                Name::new("SpeechBubbleText"),
+                Text2dBundle {
+                    text: Text::from_section(self.text, style),
+                    transform: Transform::from_xyz(0.0, 0.0, 0.001)
+                        .with_scale(Vec2::splat(0.25).extend(1.0)),
+                    ..default()
+                },
+            ))
+            .id();
+
+        // Parent
+        let backdrop = BackdropTemplate {
+            offset: self.offset,
+            size: vec2(width, 6.0),
+        }
+        .spawn(commands);
+        commands
+            .entity(backdrop)
+            .insert((
+                Name::new("SpeechBubble"),
+                Lifetime(self.lifetime),
+                SpeechBubble,
+            ))
+            .add_child(text);
+
+        backdrop
+    }
+}
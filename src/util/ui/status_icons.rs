@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+
+use crate::common::UpdateSet;
+use crate::game::combat::status::StatusEffects;
+use crate::game::combat::status::StatusKind;
+use crate::util::animation::offset::Offset;
+
+pub struct StatusIconsPlugin;
+
+impl Plugin for StatusIconsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<StatusIconRow>()
+            .register_type::<StatusIcon>()
+            .add_systems(Update, update_status_icons.in_set(UpdateSet::UpdateUi));
+    }
+}
+
+#[derive(Component, Reflect)]
+pub struct StatusIconRow;
+
+#[derive(Component, Reflect)]
+pub struct StatusIcon;
+
+fn update_status_icons(
+    row_query: Query<(&Parent, &Children), With<StatusIconRow>>,
+    status_query: Query<&StatusEffects>,
+    mut icon_query: Query<(&mut Sprite, &mut Visibility), With<StatusIcon>>,
+) {
+    for (parent, children) in &row_query {
+        let Ok(status_effects) = status_query.get(parent.get()) else {
+            continue;
+        };
+
+        // One icon per kind of effect, even if several instances are active
+        let mut kinds = Vec::<StatusKind>::new();
+        for effect in &status_effects.0 {
+            if !kinds.contains(&effect.kind) {
+                kinds.push(effect.kind);
+            }
+        }
+
+        for (i, &child) in children.iter().enumerate() {
+            let Ok((mut sprite, mut visibility)) = icon_query.get_mut(child) else {
+                continue;
+            };
+
+            if let Some(kind) = kinds.get(i) {
+                sprite.color = kind.color();
+                *visibility = Visibility::Inherited;
+            } else {
+                *visibility = Visibility::Hidden;
+            }
+        }
+    }
+}
+
+pub struct StatusIconsTemplate {
+    pub offset: Transform,
+}
+
+impl StatusIconsTemplate {
+    const MAX_ICONS: usize = 4;
+    const ICON_SIZE: f32 = 2.5;
+    const SPACING: f32 = 3.5;
+
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        // Children
+        let icons = (0..Self::MAX_ICONS)
+            .map(|i| {
+                commands
+                    .spawn((
+                        Name::new("StatusIcon"),
+                        SpriteBundle {
+                            sprite: Sprite {
+                                custom_size: Some(Vec2::splat(Self::ICON_SIZE)),
+                                ..default()
+                            },
+                            transform: Transform::from_xyz(i as f32 * Self::SPACING, 0.0, 0.001),
+                            visibility: Visibility::Hidden,
+                            ..default()
+                        },
+                        StatusIcon,
+                    ))
+                    .id()
+            })
+            .collect::<Vec<_>>();
+
+        // Parent
+        commands
+            .spawn((
+                Name::new("StatusIcons"),
+                SpatialBundle::default(),
+                Offset(self.offset),
+                StatusIconRow,
+            ))
+            .push_children(&icons)
+            .id()
+    }
+}
@@ -76,3 +76,28 @@ impl AlertPopupTemplate {
             .id()
     }
 }
+
+pub struct PickupFlashTemplate {
+    pub transform: Transform,
+    pub color: Color,
+}
+
+impl PickupFlashTemplate {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                Name::new("PickupFlash"),
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: self.color.with_a(0.5),
+                        custom_size: Some(Vec2::splat(12.0)),
+                        ..default()
+                    },
+                    transform: self.transform,
+                    ..default()
+                },
+                Lifetime(0.15),
+            ))
+            .id()
+    }
+}